@@ -0,0 +1,74 @@
+use rand::{Rng};
+use rand::distributions::normal::{StandardNormal};
+
+use util::config::{Config, ConfigError, ConfigErrors, Section};
+
+// the mutation strength never leaves this range.
+const MIN_STRENGTH: f64 = 0.001;
+const MAX_STRENGTH: f64 = 1.0;
+
+/// how the mutation strength changes during a run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Adaptation {
+    Fixed,          // `mutation` throughout
+    LogNormal(f64), // every child inherits its parent's strength, times exp(rate * N(0, 1))
+    OneFifth(f64),  // one strength for all, divided by the factor if over a fifth of children beat their parent, else multiplied
+}
+
+fn clamp(strength: f64) -> f64 {
+    strength.max(MIN_STRENGTH).min(MAX_STRENGTH)
+}
+
+impl Adaptation {
+    /// `adaptation` (default "fixed"), with `learning_rate` (default
+    /// 0.3) for "log-normal" or `adaptation_factor` (default 0.85)
+    /// for "one-fifth".
+    pub fn from_config(config: &Config, vars: &Section) -> Result<Adaptation, ConfigErrors> {
+        let name = try!(config.get_default(vars, "adaptation", "fixed".to_string()));
+        match &name[..] {
+            "fixed" => Ok(Adaptation::Fixed),
+            "log-normal" => {
+                let rate: f64 = try!(config.get_default(vars, "learning_rate", 0.3));
+                if rate <= 0.0 {
+                    let why = format!("must be more than 0.0, not {}.", rate);
+                    return Err(ConfigError::invalid(vars.key("learning_rate"), why).into());
+                }
+                Ok(Adaptation::LogNormal(rate))
+            }
+            "one-fifth" => {
+                let factor: f64 = try!(config.get_default(vars, "adaptation_factor", 0.85));
+                if factor <= 0.0 || factor >= 1.0 {
+                    let why = format!("must be between 0.0 and 1.0 (exclusive), not {}.", factor);
+                    return Err(ConfigError::invalid(vars.key("adaptation_factor"), why).into());
+                }
+                Ok(Adaptation::OneFifth(factor))
+            }
+            _ => {
+                let why = format!("unknown adaptation `{}` (expected fixed, log-normal or one-fifth).", name);
+                Err(ConfigError::invalid(vars.key("adaptation"), why).into())
+            }
+        }
+    }
+
+    /// the strength a child is made with, given its parent's and the
+    /// run's current one.
+    pub fn inherit<R: Rng>(&self, rng: &mut R, parent: f64, current: f64) -> f64 {
+        match *self {
+            Adaptation::LogNormal(rate) => {
+                let StandardNormal(n) = rng.gen::<StandardNormal>();
+                clamp(parent * (rate * n).exp())
+            }
+            _ => current
+        }
+    }
+
+    /// the run's strength for the next iteration, given the fraction
+    /// of this iteration's children that beat their parent.
+    pub fn adapt(&self, current: f64, successes: f64) -> f64 {
+        match *self {
+            Adaptation::OneFifth(factor) if successes > 0.2 => clamp(current / factor),
+            Adaptation::OneFifth(factor) if successes < 0.2 => clamp(current * factor),
+            _ => current
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use std::path::{Path};
+use std::io::{Read, Write};
+use std::fs::{File};
+use std::collections::{HashMap};
+
+use chapters::chapter2::celloption::{CellOptions, CellOption, Tile, Occupant, Item};
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::phenotype::{Seed};
+use util::config::{Config, ConfigError, ConfigErrorKind, ConfigErrors, Section};
+
+/// converts dungeons to and from plain-text maps. a map is the tile
+/// layer (one line per row, top to bottom), a blank line, and then an
+/// occupant overlay of the same size. dungeons with items get a third
+/// layer, after another blank line, showing the first item of every
+/// cell (any others are not written).
+#[derive(Clone, Debug)]
+pub struct AsciiMap {
+    tiles: HashMap<char, Tile>,
+    occupants: HashMap<char, Occupant>,
+    items: HashMap<char, Item>,
+    empty: char,
+    no_occupant: char,
+    no_item: char,
+}
+
+impl AsciiMap {
+    /// every tile, occupant and item of the seed needs a symbol of its
+    /// own, which cannot be its layer's blank (`empty`, `no_occupant`
+    /// or `no_item`).
+    pub fn new(config: &Config, seed: &Seed) -> Result<AsciiMap, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let map_vars = try!(config.get_table(None, "ascii-map"));
+        let empty = errors.check(config.get_char(&map_vars, "empty"));
+        let no_occupant = errors.check(config.get_char(&map_vars, "no_occupant"));
+        let no_item = if map_vars.contains("no_item") {
+            errors.check(config.get_char(&map_vars, "no_item"))
+        } else {
+            Some('.')
+        };
+        let tile_vars = try!(config.get_table(Some(&map_vars), "tiles"));
+        let tiles = read_layer(config, &tile_vars, &seed.tiles, "tile", (empty, map_vars.key("empty")), &mut errors);
+        let occupant_vars = try!(config.get_table(Some(&map_vars), "occupants"));
+        let occupants = read_layer(config, &occupant_vars, &seed.occupants, "occupant", (no_occupant, map_vars.key("no_occupant")), &mut errors);
+        let item_vars = try!(config.get_optional_table(Some(&map_vars), "items"));
+        let items = read_layer(config, &item_vars, &seed.items, "item", (no_item, map_vars.key("no_item")), &mut errors);
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(AsciiMap {
+            tiles: tiles,
+            occupants: occupants,
+            items: items,
+            empty: empty.unwrap(),
+            no_occupant: no_occupant.unwrap(),
+            no_item: no_item.unwrap(),
+        })
+    }
+
+    // reverse lookup: which character stands for this name? `new`
+    // made sure that every option of the seed has one.
+    fn symbol<T: CellOption>(mapping: &HashMap<char, T>, name: &str) -> char {
+        match mapping.iter().find(|&(_, option)| &option.name()[..] == name) {
+            Some((&ch, _)) => ch,
+            None => panic!("`{}` is not an option of the seed this map was made for.", name)
+        }
+    }
+
+    pub fn to_string(&self, dungeon: &Dungeon) -> String {
+        let mut tile_layer = String::new();
+        let mut occupant_layer = String::new();
+        let mut item_layer = String::new();
+        let mut has_items = false;
+        for y in 0..dungeon.height as u32 {
+            for x in 0..dungeon.width as u32 {
+                let cell = dungeon.get_cell(x, y);
+                tile_layer.push(match cell.tile {
+                    Some(ref tile) => AsciiMap::symbol(&self.tiles, &tile.name()[..]),
+                    None => self.empty
+                });
+                occupant_layer.push(match cell.occupant {
+                    Some(ref occupant) => AsciiMap::symbol(&self.occupants, &occupant.name()[..]),
+                    None => self.no_occupant
+                });
+                item_layer.push(match cell.items.first() {
+                    Some(item) => {
+                        has_items = true;
+                        AsciiMap::symbol(&self.items, &item.name()[..])
+                    }
+                    None => self.no_item
+                });
+            }
+            tile_layer.push('\n');
+            occupant_layer.push('\n');
+            item_layer.push('\n');
+        }
+        if has_items {
+            format!("{}\n{}\n{}", tile_layer, occupant_layer, item_layer)
+        } else {
+            format!("{}\n{}", tile_layer, occupant_layer)
+        }
+    }
+
+    pub fn from_str(&self, map: &str) -> Result<Dungeon, String> {
+        let lines: Vec<&str> = map.lines().collect();
+        let layers: Vec<&[&str]> = lines.split(|line| line.is_empty()).filter(|layer| !layer.is_empty()).collect();
+        let (tile_layer, occupant_layer, item_layer) = match layers.len() {
+            1 => (layers[0], None, None),
+            2 => (layers[0], Some(layers[1]), None),
+            3 => (layers[0], Some(layers[1]), Some(layers[2])),
+            n => return Err(format!("expected a tile layer and optional occupant and item layers, found {} layers.", n))
+        };
+        let height = tile_layer.len();
+        let width = tile_layer[0].chars().count();
+        let mut dungeon = Dungeon::new(width as u32, height as u32, None);
+        for (y, line) in tile_layer.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(format!("tile row {} is not {} characters wide.", y, width));
+            }
+            for (x, ch) in line.chars().enumerate() {
+                if ch == self.empty {
+                    continue;
+                }
+                match self.tiles.get(&ch) {
+                    Some(tile) => dungeon.set_tile(x as u32, y as u32, tile),
+                    None => return Err(format!("unknown tile symbol {:?} at ({}, {}).", ch, x, y))
+                }
+            }
+        }
+        if let Some(overlay) = occupant_layer {
+            if overlay.len() != height {
+                return Err(format!("occupant layer has {} rows, expected {}.", overlay.len(), height));
+            }
+            for (y, line) in overlay.iter().enumerate() {
+                if line.chars().count() != width {
+                    return Err(format!("occupant row {} is not {} characters wide.", y, width));
+                }
+                for (x, ch) in line.chars().enumerate() {
+                    if ch == self.no_occupant {
+                        continue;
+                    }
+                    match self.occupants.get(&ch) {
+                        Some(occupant) => dungeon.set_occupant(x as u32, y as u32, occupant),
+                        None => return Err(format!("unknown occupant symbol {:?} at ({}, {}).", ch, x, y))
+                    }
+                }
+            }
+        }
+        if let Some(overlay) = item_layer {
+            if overlay.len() != height {
+                return Err(format!("item layer has {} rows, expected {}.", overlay.len(), height));
+            }
+            for (y, line) in overlay.iter().enumerate() {
+                if line.chars().count() != width {
+                    return Err(format!("item row {} is not {} characters wide.", y, width));
+                }
+                for (x, ch) in line.chars().enumerate() {
+                    if ch == self.no_item {
+                        continue;
+                    }
+                    match self.items.get(&ch) {
+                        Some(item) => dungeon.add_item(x as u32, y as u32, item),
+                        None => return Err(format!("unknown item symbol {:?} at ({}, {}).", ch, x, y))
+                    }
+                }
+            }
+        }
+        Ok(dungeon)
+    }
+
+    pub fn save(&self, path: &Path, dungeon: &Dungeon) {
+        let mut file = match File::create(path) {
+            Ok(f) => f,
+            Err(why) => panic!("Could not create {}: {}", path.display(), why)
+        };
+        match file.write_all(self.to_string(dungeon).as_bytes()) {
+            Err(why) => panic!("Could not write {}: {}", path.display(), why),
+            _ => ()
+        }
+    }
+
+    pub fn load(&self, path: &Path) -> Result<Dungeon, String> {
+        let mut file = match File::open(path) {
+            Ok(f) => f,
+            Err(why) => return Err(format!("could not open {}: {}", path.display(), why))
+        };
+        let mut contents = String::new();
+        match file.read_to_string(&mut contents) {
+            Err(why) => return Err(format!("could not read {}: {}", path.display(), why)),
+            _ => ()
+        };
+        self.from_str(&contents[..]).map_err(|why| format!("{}: {}", path.display(), why))
+    }
+}
+
+// one layer's symbols, from its table: one per option of the seed,
+// all different, and none the same as the layer's blank.
+fn read_layer<T: CellOption + Clone>(config: &Config, vars: &Section, options: &CellOptions<T>, kind: &str,
+                                     (blank, blank_key): (Option<char>, String), errors: &mut ConfigErrors) -> HashMap<char, T> {
+    let mut symbols: HashMap<char, T> = HashMap::new();
+    for name in config.get_listing(vars, vec![]) {
+        match (options.get(&name[..]), errors.check(config.get_char(vars, &name[..]))) {
+            (Some(option), Some(ch)) => {
+                let taken = symbols.get(&ch).map(|other| other.name());
+                if Some(ch) == blank {
+                    errors.push(ConfigError::invalid(vars.key(&name[..]), format!("is the same as `{}`.", blank_key)));
+                } else if let Some(other) = taken {
+                    errors.push(ConfigError::invalid(vars.key(&name[..]), format!("is also the symbol of `{}`.", other)));
+                } else {
+                    symbols.insert(ch, option.clone());
+                }
+            }
+            (None, _) => errors.push(ConfigError::invalid(vars.key(&name[..]), format!("is not a known {}.", kind))),
+            _ => ()
+        }
+    }
+    for name in options.names() {
+        if !vars.contains(&name[..]) {
+            errors.push(ConfigError::new(vars.key(&name[..]), ConfigErrorKind::Missing));
+        }
+    }
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path};
+    use std::io::{Read};
+    use std::fs::{File};
+    use toml::{Value};
+
+    use chapters::chapter2::celloption::{CellOptions, CellOption};
+    use chapters::chapter2::genotype::{Genotype};
+    use chapters::chapter2::phenotype::{Seed};
+    use chapters::chapter2::random_seed::{RandomSeed};
+    use util::config::{Config};
+    use util::util::{derive_rng};
+    use super::{AsciiMap};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn seed() -> Seed {
+        Seed::new(9, 7,
+                  CellOptions::new(&names(&["floor", "wall", "entrance", "exit", "door"])[..]),
+                  CellOptions::new(&names(&["key"])[..]),
+                  CellOptions::new(&names(&["monster", "treasure", "trap", "teleporter"])[..]),
+                  0.2)
+    }
+
+    fn ascii_map() -> AsciiMap {
+        let mut config = Config::new(&Path::new(env!("CARGO_MANIFEST_DIR")).join("config").join("chapter2.toml")).unwrap();
+        config.set("ascii-map.items.key", Value::String("k".to_string())).unwrap();
+        AsciiMap::new(&config, &seed()).unwrap()
+    }
+
+    fn golden(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(name);
+        let mut contents = String::new();
+        File::open(&path).and_then(|mut file| file.read_to_string(&mut contents)).unwrap();
+        contents
+    }
+
+    #[test]
+    fn golden_maps_round_trip() {
+        let map = ascii_map();
+        for name in ["room.txt", "items.txt"].iter() {
+            let contents = golden(name);
+            let dungeon = map.from_str(&contents[..]).unwrap();
+            assert_eq!(map.to_string(&dungeon), contents);
+        }
+    }
+
+    #[test]
+    fn layers_are_read() {
+        let map = ascii_map();
+        let dungeon = map.from_str(&golden("items.txt")[..]).unwrap();
+        assert_eq!((dungeon.width, dungeon.height), (5, 4));
+        assert!(dungeon.has_attribute(0, 2, "entrance"));
+        assert!(dungeon.get_cell(2, 2).tile.is_none());
+        assert_eq!(dungeon.get_cell(1, 1).occupant.as_ref().map(|o| o.name()), Some("monster".to_string()));
+        assert_eq!(dungeon.get_cell(3, 1).items.len(), 1);
+        assert!(dungeon.get_cell(3, 2).items.is_empty());
+    }
+
+    #[test]
+    fn generated_dungeons_round_trip() {
+        let map = ascii_map();
+        let genotype = RandomSeed::new(&seed());
+        for i in 0..5 {
+            let mut rng = derive_rng(i, &[]);
+            let dungeon = genotype.initialize(&mut rng).generate();
+            let loaded = map.from_str(&map.to_string(&dungeon)[..]).unwrap();
+            assert_eq!(loaded.fingerprint(), dungeon.fingerprint());
+        }
+    }
+
+    #[test]
+    fn ragged_maps_are_rejected() {
+        let map = ascii_map();
+        assert!(map.from_str("XXX\nXX\n").is_err());
+        assert!(map.from_str("XXX\nX_X\n\n...\n").is_err());
+        assert!(map.from_str("XZX\n").is_err());
+    }
+
+    // the problems with the ASCII map settings after an override.
+    fn problems(key: &str, value: &str) -> Vec<String> {
+        let mut config = Config::new(&Path::new(env!("CARGO_MANIFEST_DIR")).join("config").join("chapter2.toml")).unwrap();
+        config.set("ascii-map.items.key", Value::String("k".to_string())).unwrap();
+        config.set(key, Value::String(value.to_string())).unwrap();
+        match AsciiMap::new(&config, &seed()) {
+            Ok(_) => vec![],
+            Err(errors) => errors.errors.iter().map(|error| error.key.clone()).collect()
+        }
+    }
+
+    #[test]
+    fn symbols_are_checked() {
+        assert!(problems("ascii-map.tiles.door", "D").is_empty());
+        // used twice in one layer, but fine in another.
+        assert_eq!(problems("ascii-map.tiles.door", "X").len(), 1);
+        assert!(problems("ascii-map.items.key", "X").is_empty());
+        // the same as the layer's blank.
+        assert_eq!(problems("ascii-map.tiles.door", "?"), vec!["ascii-map.tiles.door".to_string()]);
+        assert_eq!(problems("ascii-map.occupants.trap", "."), vec!["ascii-map.occupants.trap".to_string()]);
+        assert_eq!(problems("ascii-map.no_item", "k"), vec!["ascii-map.items.key".to_string()]);
+    }
+
+    #[test]
+    fn every_option_needs_a_symbol() {
+        let config = Config::new(&Path::new(env!("CARGO_MANIFEST_DIR")).join("config").join("chapter2.toml")).unwrap();
+        let errors = AsciiMap::new(&config, &seed()).unwrap_err();
+        assert_eq!(errors.errors.iter().map(|error| error.key.clone()).collect::<Vec<_>>(), vec!["ascii-map.items.key".to_string()]);
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher, SipHasher};
+
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic};
+
+/// a hash of a genotype's genome (see `Genotype::encode_genome`):
+/// equal genotypes have equal hashes.
+pub fn genome_hash<G: Genotype>(genotype: &G) -> u64 {
+    let mut hasher = SipHasher::new();
+    genotype.encode_genome().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// the statistics of recently evaluated genotypes, by `genome_hash`,
+/// so that duplicates are only evaluated once. phenotypes are not
+/// kept: a dungeon is generated again only if it is needed. when
+/// full, the oldest entry makes way.
+pub struct Cache {
+    capacity: usize,
+    entries: HashMap<u64, Statistic>,
+    order: VecDeque<u64>, // oldest first
+}
+
+impl Cache {
+    pub fn new(capacity: usize) -> Cache {
+        Cache {
+            capacity: capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&self, key: u64) -> Option<Statistic> {
+        self.entries.get(&key).cloned()
+    }
+
+    pub fn insert(&mut self, key: u64, statistic: Statistic) {
+        if self.capacity == 0 || self.entries.contains_key(&key) {
+            return;
+        }
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+        self.entries.insert(key, statistic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, XorShiftRng};
+    use chapters::chapter2::celloption::{CellOptions};
+    use chapters::chapter2::genotype::{Genotype};
+    use chapters::chapter2::phenotype::{Seed};
+    use chapters::chapter2::random_seed::{RandomSeed};
+    use super::{genome_hash};
+
+    fn seed(width: u32) -> Seed {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<String>>();
+        Seed::new(width, 10,
+                  CellOptions::new(&names(&["floor", "wall"])[..]),
+                  CellOptions::new(&names(&["chest"])[..]),
+                  CellOptions::new(&names(&["orc"])[..]),
+                  0.1)
+    }
+
+    #[test]
+    fn only_the_genome_is_hashed() {
+        assert_eq!(genome_hash(&RandomSeed::new(&seed(10))), genome_hash(&RandomSeed::new(&seed(20))));
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let genotype = RandomSeed::new(&seed(10)).initialize(&mut rng);
+        assert!(genome_hash(&genotype) != genome_hash(&RandomSeed::new(&seed(10))));
+        assert_eq!(genome_hash(&genotype), genome_hash(&genotype.clone()));
+    }
+}
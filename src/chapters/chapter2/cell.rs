@@ -1,6 +1,6 @@
-use chapter2::celloption::{CellOption, Tile, Occupant, Item};
+use chapters::chapter2::celloption::{CellOption, Tile, Occupant, Item};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Cell {
     pub x: u32,
     pub y: u32,
@@ -1,4 +1,5 @@
 use rand::{Rng, sample};
+use rustc_serialize::{Encodable, Decodable, Encoder, Decoder};
 use std::marker::{PhantomData};
 
 /// cell options store data for a particular cell type.
@@ -28,7 +29,21 @@ impl<A> CellOption for CellData<A> {
     }
 }
 
-#[derive(Clone, Debug)]
+// by hand, as deriving would require the marker type to be
+// serialisable too: a cell option is just its name.
+impl<A> Encodable for CellData<A> {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        self.data.encode(s)
+    }
+}
+
+impl<A> Decodable for CellData<A> {
+    fn decode<D: Decoder>(d: &mut D) -> Result<CellData<A>, D::Error> {
+        Ok(CellOption::new(try!(String::decode(d))))
+    }
+}
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct CellOptions<T> {
     options: Vec<T>
 }
@@ -51,6 +66,10 @@ impl<T: CellOption> CellOptions<T> {
         }
     }
 
+    pub fn names(&self) -> Vec<String> {
+        self.options.iter().map(|opt| opt.name()).collect()
+    }
+
     pub fn choose<R: Rng>(&self, rng: &mut R) -> &T {
         assert!(self.options.len() > 0, "Cannot choose random cell option.");
         sample(rng, self.options.iter(), 1).into_iter().next().unwrap()
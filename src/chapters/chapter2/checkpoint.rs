@@ -0,0 +1,51 @@
+use std::path::{Path};
+use std::io::{Read, Write};
+use std::fs::{File, rename};
+use rustc_serialize::json;
+
+use chapters::chapter2::statistics::{Statistic, Generation};
+
+/// everything needed to carry on a run where it left off. there is no
+/// random number generator state as such: every random stream is
+/// derived from the run seed and the iteration, so the seed is enough.
+/// the engine, strategy, mu and lambda are kept so that a checkpoint
+/// is only resumed by a run it fits.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct Checkpoint {
+    pub engine: String,
+    pub strategy: String,
+    pub mu: usize,
+    pub lambda: usize,
+    pub iteration: u32, // the next iteration to run
+    pub rng_seed: u64,
+    pub mutation: f64, // the current mutation strength
+    pub strengths: Vec<f64>,
+    pub evaluations: usize, // dungeons evaluated so far
+    pub history: Vec<Generation>,
+    pub population: Vec<(String, Statistic)>, // genotypes as JSON
+    pub novelty_archive: Vec<Vec<f64>>,
+}
+
+impl Checkpoint {
+    pub fn load(path: &Path) -> Result<Checkpoint, String> {
+        let mut contents = String::new();
+        let mut file = try!(File::open(path).map_err(|why| format!("could not open {}: {}", path.display(), why)));
+        try!(file.read_to_string(&mut contents).map_err(|why| format!("could not read {}: {}", path.display(), why)));
+        json::decode(&contents[..]).map_err(|why| format!("{} is not a checkpoint: {}", path.display(), why))
+    }
+
+    /// write to a temporary file first, so that a run killed while
+    /// saving still leaves the previous checkpoint intact.
+    pub fn save(&self, path: &Path) {
+        let temporary = path.with_extension("tmp");
+        let encoded = json::encode(self).unwrap();
+        match File::create(&temporary).and_then(|mut file| file.write_all(encoded.as_bytes())) {
+            Err(why) => panic!("Could not write {}: {}", temporary.display(), why),
+            _ => ()
+        }
+        match rename(&temporary, path) {
+            Err(why) => panic!("Could not write {}: {}", path.display(), why),
+            _ => ()
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use rand::{Rng};
+
+use util::config::{Config, ConfigError, ConfigErrors, Section};
+
+/// how two parents' genes are combined.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Crossover {
+    OnePoint, // the first parent up to a random point, then the second
+    Uniform,  // every gene from either parent, at random
+}
+
+impl Crossover {
+    /// `crossover`, either "one-point" (the default) or "uniform".
+    pub fn from_config(config: &Config, vars: &Section) -> Result<Crossover, ConfigErrors> {
+        let name = try!(config.get_default(vars, "crossover", "one-point".to_string()));
+        match &name[..] {
+            "one-point" => Ok(Crossover::OnePoint),
+            "uniform" => Ok(Crossover::Uniform),
+            _ => {
+                let why = format!("unknown crossover `{}` (expected one-point or uniform).", name);
+                Err(ConfigError::invalid(vars.key("crossover"), why).into())
+            }
+        }
+    }
+
+    /// combine two lists of genes. the child is as long as `first`;
+    /// where `second` is shorter, the genes come from `first`.
+    pub fn recombine<R: Rng, T: Clone>(&self, rng: &mut R, first: &[T], second: &[T]) -> Vec<T> {
+        let shared = first.len().min(second.len());
+        match *self {
+            Crossover::OnePoint => {
+                let point = if shared == 0 { 0 } else { rng.gen_range(0, shared + 1) };
+                first.iter().enumerate().map(|(i, gene)| {
+                    if i >= point && i < shared { second[i].clone() } else { gene.clone() }
+                }).collect()
+            }
+            Crossover::Uniform => {
+                first.iter().enumerate().map(|(i, gene)| {
+                    if i < shared && rng.gen() { second[i].clone() } else { gene.clone() }
+                }).collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use util::util::{derive_rng};
+    use super::{Crossover};
+
+    #[test]
+    fn one_point_switches_parents_once() {
+        let (first, second) = (vec![0; 10], vec![1; 10]);
+        for seed in 0..20 {
+            let mut rng = derive_rng(seed, &[]);
+            let child = Crossover::OnePoint.recombine(&mut rng, &first[..], &second[..]);
+            assert_eq!(child.len(), 10);
+            // a run of the first parent's genes, then the second's.
+            let switches = child.windows(2).filter(|pair| pair[0] != pair[1]).count();
+            assert!(switches <= 1);
+            assert!(child.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+    }
+
+    #[test]
+    fn uniform_takes_genes_from_both() {
+        let (first, second) = (vec![0; 100], vec![1; 100]);
+        let mut rng = derive_rng(3, &[]);
+        let child = Crossover::Uniform.recombine(&mut rng, &first[..], &second[..]);
+        let from_second = child.iter().filter(|&&gene| gene == 1).count();
+        assert!(from_second > 20 && from_second < 80);
+    }
+
+    #[test]
+    fn the_child_is_as_long_as_the_first_parent() {
+        let (long, short) = (vec![0; 8], vec![1; 3]);
+        for crossover in [Crossover::OnePoint, Crossover::Uniform].iter() {
+            let mut rng = derive_rng(4, &[]);
+            let child = crossover.recombine(&mut rng, &long[..], &short[..]);
+            assert_eq!(child.len(), 8);
+            assert!(child[3..].iter().all(|&gene| gene == 0));
+            let child = crossover.recombine(&mut rng, &short[..], &long[..]);
+            assert_eq!(child.len(), 3);
+            let child: Vec<i32> = crossover.recombine(&mut rng, &[], &long[..]);
+            assert!(child.is_empty());
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use chapters::chapter2::dungeon::{Dungeon, DungeonCells};
+use chapters::chapter2::evaluation::{count_rooms};
+use chapters::chapter2::pathfinding::{Pathfinder, entrance_and_exit};
+
+/// a behaviour descriptor: measures some aspect of a dungeon (not how
+/// good it is), so that different kinds of dungeons can be told apart.
+pub type DescriptorFn = Box<Fn(&Dungeon) -> f64 + 'static + Send + Sync>;
+
+/// characterises a dungeon as a vector (e.g. several descriptors).
+pub type BehaviourFn = Box<Fn(&Dungeon) -> Vec<f64> + 'static + Send + Sync>;
+
+/// the behaviour made up of these descriptors, in order.
+pub fn combine(descriptors: Vec<DescriptorFn>) -> BehaviourFn {
+    box move |dungeon: &Dungeon| descriptors.iter().map(|f| f(dungeon)).collect()
+}
+
+// the descriptors that `[map-elites.x] descriptor` can name.
+static DESCRIPTORS: [&'static str; 4] = ["room_count", "path_length", "wall_density", "door_count"];
+
+pub fn room_count(dungeon: &Dungeon) -> f64 {
+    count_rooms(dungeon) as f64
+}
+
+/// the number of steps from the entrance to the exit, or 0.0 if there
+/// is no way through (or no entrance or exit).
+pub fn path_length(dungeon: &Dungeon) -> f64 {
+    let pathfinder = Pathfinder::default();
+    entrance_and_exit(dungeon)
+        .and_then(|(start, goal)| pathfinder.bfs(dungeon, start, goal))
+        .map_or(0.0, |path| (path.len() - 1) as f64)
+}
+
+/// the fraction of cells that are walls.
+pub fn wall_density(dungeon: &Dungeon) -> f64 {
+    let walls = DungeonCells::new(dungeon).filter(|cell| cell.has_attribute("wall")).count();
+    walls as f64 / (dungeon.width * dungeon.height) as f64
+}
+
+pub fn door_count(dungeon: &Dungeon) -> f64 {
+    DungeonCells::new(dungeon).filter(|cell| cell.has_attribute("door")).count() as f64
+}
+
+/// look up a descriptor by name. an unknown name is an error that
+/// lists the valid ones.
+pub fn descriptor(name: &str) -> Result<DescriptorFn, String> {
+    match name {
+        "room_count" => Ok(box room_count),
+        "path_length" => Ok(box path_length),
+        "wall_density" => Ok(box wall_density),
+        "door_count" => Ok(box door_count),
+        _ => Err(format!("unknown descriptor `{}` (expected one of {}).", name, DESCRIPTORS.join(", ")))
+    }
+}
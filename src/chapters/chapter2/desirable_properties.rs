@@ -1,14 +1,15 @@
-use chapter2::dungeon::{Dungeon, SurroundingCells, Surrounding};
-use chapter2::celloption::{Occupant};
-use chapter2::genotype::{Genotype};
-use chapter2::phenotype::{Seed};
-use util::config::{Config};
+use chapters::chapter2::dungeon::{Dungeon, SurroundingCells, Surrounding};
+use chapters::chapter2::celloption::{Occupant};
+use chapters::chapter2::genotype::{Genotype, to_json, from_json};
+use chapters::chapter2::crossover::{Crossover};
+use chapters::chapter2::phenotype::{Seed};
+use util::config::{Config, ConfigError, ConfigErrors};
 use util::util::{odds};
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 use rand::{Rng};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct DesirableProperties {
     seed: Seed,
     room_number: u32,
@@ -24,7 +25,7 @@ pub struct DesirableProperties {
     exit: (u32, u32),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Room {
     x: u32,
     y: u32,
@@ -33,13 +34,13 @@ pub struct Room {
     region: u32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 struct Maze {
-    path: HashSet<(u32, u32)>,
+    path: BTreeSet<(u32, u32)>,
     region: u32,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 enum Direction {
     North,
     East,
@@ -60,14 +61,14 @@ fn scale(coord: (u32, u32), dir: (i32, i32), n: i32) -> (u32, u32) {
     (((x as i32 + dx * n) as u32), ((y as i32 + dy * n) as u32))
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 struct Connector {
     location: (u32, u32),
-    regions: HashSet<u32>,
+    regions: BTreeSet<u32>,
 }
 
 impl Connector {
-    pub fn new(location: (u32, u32), regions: &HashSet<u32>) -> Connector {
+    pub fn new(location: (u32, u32), regions: &BTreeSet<u32>) -> Connector {
         Connector {
             location: location,
             regions: regions.clone(),
@@ -76,7 +77,7 @@ impl Connector {
 
     pub fn find_all(mazes: &Vec<Maze>, rooms: &Vec<Room>) -> Vec<Connector> {
         // build up a hashmap of coords to region id
-        let mut lookup: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut lookup: BTreeMap<(u32, u32), u32> = BTreeMap::new();
         for maze in mazes {
             for coord in maze.clone().path {
                 lookup.insert(coord, maze.region);
@@ -89,13 +90,13 @@ impl Connector {
         }
         // find connectors (blank spaces between two regions)
         let mut connectors: Vec<Connector> = Vec::new();
-        let mut examined: HashSet<(u32, u32)> = HashSet::new();
+        let mut examined: BTreeSet<(u32, u32)> = BTreeSet::new();
         for (&coord, &region) in lookup.iter() {
             examined.insert(coord);
             let possibles: Vec<(u32, u32)> = CARDINALS.iter().map(|&(_, rel_dir)| scale(coord, rel_dir, 1)).collect();
             for new_coord in possibles {
                 if !examined.contains(&new_coord) || !lookup.contains_key(&new_coord) {
-                    let regions: HashSet<u32> = CARDINALS.iter().map(|&(_, rel_dir)| {
+                    let regions: BTreeSet<u32> = CARDINALS.iter().map(|&(_, rel_dir)| {
                         let coord = scale(new_coord, rel_dir, 1);
                         if lookup.contains_key(&coord) {
                             lookup[&coord]
@@ -116,7 +117,7 @@ impl Connector {
 
     pub fn merge<T: Rng>(rng: &mut T, connectors: &Vec<Connector>) -> Vec<Connector> {
         // accumulate all of the regions
-        let mut open: HashSet<u32> = HashSet::new();
+        let mut open: BTreeSet<u32> = BTreeSet::new();
         for connector in connectors {
             open = open.union(&connector.regions.clone()).cloned().collect();
         }
@@ -131,7 +132,7 @@ impl Connector {
             let connector = rng.choose(&connectors[..]).unwrap();
             let mut regions: Vec<u32> = connector.regions.iter().map(|&r| merged_lookup[r as usize]).collect();
             let first = regions.pop().unwrap();
-            let rest: HashSet<u32> = regions.clone().iter().cloned().collect();
+            let rest: BTreeSet<u32> = regions.clone().iter().cloned().collect();
             merged_connectors.push(connector.clone());
             for i in 0..region_number {
                 if rest.contains(&merged_lookup[i]) {
@@ -140,7 +141,7 @@ impl Connector {
             }
             // remove unnecessary connectors
             current = current.iter().filter(|&c| {
-                let regions: HashSet<u32> = c.regions.iter().map(|&r| merged_lookup[r as usize]).collect();
+                let regions: BTreeSet<u32> = c.regions.iter().map(|&r| merged_lookup[r as usize]).collect();
                 regions.len() > 1
             }).cloned().collect();
             open = open.difference(&rest).cloned().collect();
@@ -153,7 +154,7 @@ pub type CollisionFn<'a> = Box<Fn(&(u32, u32)) -> bool + 'a>;
 
 impl Maze {
     pub fn new<T: Rng>(rng: &mut T,
-                       previous: &HashSet<(u32, u32)>,
+                       previous: &BTreeSet<(u32, u32)>,
                        collides: CollisionFn,
                        branching: f64,
                        region: u32,
@@ -167,14 +168,14 @@ impl Maze {
     }
 
     fn grow<T: Rng>(rng: &mut T,
-                    previous: &HashSet<(u32, u32)>,
+                    previous: &BTreeSet<(u32, u32)>,
                     collides: CollisionFn,
                     branching: f64,
                     orig_x: u32,
-                    orig_y: u32) -> HashSet<(u32, u32)> {
+                    orig_y: u32) -> BTreeSet<(u32, u32)> {
         let branching_factor = (branching * 100.0) as u64;
         let mut path: Vec<(u32, u32)> = Vec::new();
-        let mut all_paths: HashSet<(u32, u32)> = previous.clone();
+        let mut all_paths: BTreeSet<(u32, u32)> = previous.clone();
         let all = vec!(Direction::North, Direction::East, Direction::South, Direction::West);
         let mut direction = rng.choose(&all[..]).unwrap().clone();
         path.push((orig_x, orig_y));
@@ -182,7 +183,7 @@ impl Maze {
         // "growing-tree" algorithm
         while !path.is_empty() {
             let new_coord = path[path.len()-1];
-            let uncarved: HashMap<Direction, (i32, i32)> = CARDINALS.iter().filter(|&&(_, new_dir)| {
+            let uncarved: BTreeMap<Direction, (i32, i32)> = CARDINALS.iter().filter(|&&(_, new_dir)| {
                 let coord1 = scale(new_coord, new_dir, 1);
                 let coord2 = scale(new_coord, new_dir, 2);
                 let coord3 = scale(new_coord, new_dir, 3);
@@ -197,8 +198,10 @@ impl Maze {
                 let rel_dir = if same_direction && uncarved.contains_key(&direction) {
                     uncarved[&direction]
                 } else {
-                    let (dir, coords) = uncarved.into_iter().next().unwrap();
-                    direction = dir;
+                    // pick a random direction (map order is not random)
+                    let options: Vec<(Direction, (i32, i32))> = uncarved.into_iter().collect();
+                    let &(ref dir, coords) = rng.choose(&options[..]).unwrap();
+                    direction = dir.clone();
                     coords
                 };
                 let coord1 = scale(new_coord, rel_dir, 1);
@@ -217,8 +220,8 @@ impl Maze {
         // given a maze, make sure there are no dead ends. we need to
         // use connectors to make sure we don't have paths to other
         // regions.
-        let coords: HashSet<(u32, u32)> = connectors.iter().map(|&ref c| c.location).collect();
-        let mut extraneous: HashSet<(u32, u32)> = HashSet::new();
+        let coords: BTreeSet<(u32, u32)> = connectors.iter().map(|&ref c| c.location).collect();
+        let mut extraneous: BTreeSet<(u32, u32)> = BTreeSet::new();
         for &coord in self.path.iter() {
             let possibles: Vec<(u32, u32)> = CARDINALS.iter().map(|&(_, rel_dir)| scale(coord, rel_dir, 1)).collect();
             // if we only have one exit, consider this coord extraneous
@@ -234,7 +237,7 @@ impl Maze {
             }
         }
         // follow the extraneous ends to their inevitable conclusion
-        let mut mod_path: HashSet<(u32, u32)> = self.path.difference(&extraneous).cloned().collect();
+        let mut mod_path: BTreeSet<(u32, u32)> = self.path.difference(&extraneous).cloned().collect();
         for coord in extraneous {
             let mut new_coord = coord;
             loop {
@@ -308,29 +311,44 @@ impl Room {
 }
 
 impl DesirableProperties {
-    pub fn new(config: &Config, seed: &Seed) -> DesirableProperties {
+    pub fn new(config: &Config, seed: &Seed) -> Result<DesirableProperties, ConfigErrors> {
         // read in the configurations
-        let desirables = config.get_table(None, "desirable_patterns");
-        let room_number = config.get_integer(desirables, "room_number") as u32;
-        let room_size = config.get_integer(desirables, "room_size") as u32;
-        let doors = config.get_integer(desirables, "doors") as u32;
-        let monsters = config.get_integer(desirables, "monsters") as u32;
-        let branching = config.get_float(desirables, "branching");
-        assert!(room_number > 0);
-        DesirableProperties {
+        let mut errors = ConfigErrors::new();
+        let desirables = try!(config.get_table(None, "desirable_patterns"));
+        let room_number = errors.check(config.get_integer(&desirables, "room_number"));
+        let room_size = errors.check(config.get_integer(&desirables, "room_size"));
+        let doors = errors.check(config.get_integer(&desirables, "doors"));
+        let monsters = errors.check(config.get_integer(&desirables, "monsters"));
+        let branching = errors.check(config.get_float(&desirables, "branching"));
+        if let Some(room_number) = room_number {
+            if room_number <= 0 {
+                errors.push(ConfigError::invalid(desirables.key("room_number"), "must be positive.".to_string()));
+            }
+        }
+        if let Some(room_size) = room_size {
+            // rooms are at least 3x3, and must fit in the dungeon.
+            if room_size <= 3 || room_size as u32 >= seed.width.min(seed.height) {
+                let why = format!("must be more than 3 and less than the dungeon size, not {}.", room_size);
+                errors.push(ConfigError::invalid(desirables.key("room_size"), why));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(DesirableProperties {
             seed: seed.clone(),
-            room_size: room_size,
-            room_number: room_number,
-            doors: doors,
-            monsters: monsters,
-            branching: branching,
+            room_size: room_size.unwrap() as u32,
+            room_number: room_number.unwrap() as u32,
+            doors: doors.unwrap() as u32,
+            monsters: monsters.unwrap() as u32,
+            branching: branching.unwrap(),
             occupants: vec![],
             rooms: vec![],
             mazes: vec![],
             connectors: vec![],
             entrance: (0, 0),
             exit: (0, 0),
-        }
+        })
     }
 
     pub fn build<T: Rng>(&self, rng: &mut T, rooms: Vec<Room>, current_region: u32) -> DesirableProperties {
@@ -340,7 +358,7 @@ impl DesirableProperties {
         let mut region = current_region;
         // fill in mazes
         let mut mazes = vec![];
-        let mut positions: HashSet<(u32, u32)> = HashSet::new();
+        let mut positions: BTreeSet<(u32, u32)> = BTreeSet::new();
         let is_occupied = |x, y| rooms.clone().iter().fold(false, |accum, ref m| accum || m.contains(x, y));
         for x in (1..w).step_by(2) {
             for y in (1..h).step_by(2) {
@@ -421,7 +439,7 @@ impl Genotype for DesirableProperties {
         let length = self.rooms.len();
         let n = (length as f64 * percentage) as u32;
         for _ in 0..n {
-            let index = rng.gen_range(0, n);
+            let index = rng.gen_range(0, length) as u32;
             for _ in 0..10 {
                 let room = Room::random(rng, self.seed.width, self.seed.height, self.room_size, index);
                 if !room.intersects(&self.rooms) {
@@ -437,6 +455,40 @@ impl Genotype for DesirableProperties {
         self.exit = result.exit;
     }
 
+    fn crossover<T: Rng>(&self, rng: &mut T, other: &DesirableProperties, scheme: &Crossover) -> DesirableProperties {
+        // exchange rooms, then repair: a room that overlaps one already
+        // placed is swapped for the other parent's room, or a new random
+        // room, or else left out.
+        let mixed = scheme.recombine(rng, &self.rooms[..], &other.rooms[..]);
+        let mut rooms: Vec<Room> = vec![];
+        for (index, room) in mixed.into_iter().enumerate() {
+            let mut candidates = vec![room];
+            candidates.extend(other.rooms.get(index).cloned());
+            candidates.extend(self.rooms.get(index).cloned());
+            for _ in 0..10 {
+                candidates.push(Room::random(rng, self.seed.width, self.seed.height, self.room_size, 0));
+            }
+            if let Some(mut room) = candidates.into_iter().find(|r| !r.intersects(&rooms)) {
+                room.region = rooms.len() as u32;
+                rooms.push(room);
+            }
+        }
+        let region = rooms.len() as u32;
+        self.build(rng, rooms, region)
+    }
+
+    fn encode_json(&self) -> String {
+        to_json(self)
+    }
+
+    fn decode_json(&self, json: &str) -> Result<DesirableProperties, String> {
+        from_json(json)
+    }
+
+    fn encode_genome(&self) -> String {
+        to_json(&(&self.rooms, &self.mazes, &self.connectors, self.entrance, self.exit, &self.occupants))
+    }
+
     fn generate(&self) -> Dungeon {
         let w = self.seed.width;
         let h = self.seed.height;
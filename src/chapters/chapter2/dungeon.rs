@@ -1,10 +1,11 @@
 use std::vec::{Vec};
 use std::iter::{Iterator};
+use std::hash::{Hash, Hasher, SipHasher};
 
-use chapter2::cell::{Cell};
-use chapter2::celloption::{Tile, Occupant};
+use chapters::chapter2::cell::{Cell};
+use chapters::chapter2::celloption::{CellOption, Tile, Occupant, Item};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Dungeon {
     pub width: usize,
     pub height: usize,
@@ -38,6 +39,10 @@ impl Dungeon {
         self.cells[x as usize][y as usize].occupant = Some(occupant.clone());
     }
 
+    pub fn add_item(&mut self, x: u32, y: u32, item: &Item) {
+        self.cells[x as usize][y as usize].items.push(item.clone());
+    }
+
     pub fn has_attribute(&self, x: u32, y: u32, attribute: &str) -> bool {
         self.cells[x as usize][y as usize].has_attribute(attribute)
     }
@@ -45,6 +50,22 @@ impl Dungeon {
     pub fn is_empty(&self, x: u32, y: u32) -> bool {
         self.cells[x as usize][y as usize].is_empty()
     }
+
+    pub fn get_cell(&self, x: u32, y: u32) -> &Cell {
+        &self.cells[x as usize][y as usize]
+    }
+
+    /// a hash of the layout (tiles and occupants): equal dungeons
+    /// have equal fingerprints.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        (self.width, self.height).hash(&mut hasher);
+        for cell in self.cells.iter().flat_map(|column| column.iter()) {
+            cell.tile.as_ref().map(|t| t.name()).hash(&mut hasher);
+            cell.occupant.as_ref().map(|o| o.name()).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
 }
 
 // external iterator.
@@ -92,40 +113,36 @@ pub struct SurroundingCells {
     index: usize
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Surrounding {
     Cardinal,
     AllDirections
 }
 
+static CARDINAL: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+static ALL_DIRECTIONS: [(i32, i32); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+impl Surrounding {
+    /// the offsets of the neighbouring cells, clockwise, starting from
+    /// the top.
+    pub fn offsets(&self) -> &'static [(i32, i32)] {
+        match *self {
+            Surrounding::Cardinal => &CARDINAL,
+            Surrounding::AllDirections => &ALL_DIRECTIONS,
+        }
+    }
+}
+
 impl SurroundingCells {
     pub fn new(dungeon: &Dungeon, x: u32, y: u32, around: Surrounding) -> SurroundingCells {
         let ref cell = dungeon.cells[x as usize][y as usize];
         let x = cell.x as i32;
         let y = cell.y as i32;
-        // clockwise, starting from the top
-        let coords = match around {
-            Surrounding::Cardinal => {
-                let invalid = (-1, -1);
-                [(x  , y-1),
-                 (x+1, y  ),
-                 (x  , y+1),
-                 (x-1, y  ),
-                 invalid,
-                 invalid,
-                 invalid,
-                 invalid]
-            }
-            Surrounding::AllDirections => {
-                [(x  , y-1),
-                 (x+1, y-1),
-                 (x+1, y  ),
-                 (x+1, y+1),
-                 (x  , y+1),
-                 (x-1, y+1),
-                 (x-1, y  ),
-                 (x-1, y-1)]
-            }
-        };
+        // unused slots stay invalid.
+        let mut coords = [(-1, -1); 8];
+        for (coord, &(dx, dy)) in coords.iter_mut().zip(around.offsets().iter()) {
+            *coord = (x + dx, y + dy);
+        }
         SurroundingCells {
             dungeon: dungeon.clone(),
             coords: coords,
@@ -0,0 +1,1232 @@
+use freetype::{Face};
+use opengl_graphics::{GlGraphics};
+use graphics::{Viewport, Line, default_draw_state};
+use input::{Event, Button, RenderEvent, PressEvent, MouseCursorEvent};
+use input::keyboard::{Key};
+use input::mouse::{MouseButton};
+use num_cpus::{get};
+use rand::{Rng, thread_rng};
+use toml::{Value};
+
+use std::path::{Path, PathBuf};
+use std::io::{Write, stderr};
+use std::f64;
+use std::cmp;
+use std::mem;
+use std::cell::{RefCell};
+use std::sync::atomic::{AtomicUsize, AtomicIsize, AtomicBool, Ordering, ATOMIC_USIZE_INIT, ATOMIC_ISIZE_INIT, ATOMIC_BOOL_INIT};
+
+use chapters::chapter2::celloption::{CellOptions, CellOption, Tile, Item, Occupant};
+use chapters::chapter2::dungeon::{Dungeon, DungeonCells};
+use chapters::chapter2::genotype::{Genotype, AnyGenotype};
+use chapters::chapter2::registry::{Registry, GenotypeRegistry, EvaluationRegistry};
+use chapters::chapter2::mu_lambda::{MuLambda};
+use chapters::chapter2::nsga2::{Nsga2};
+use chapters::chapter2::map_elites::{MapElites, Dimension};
+use chapters::chapter2::novelty::{Novelty};
+use chapters::chapter2::fi2pop::{Fi2Pop};
+use chapters::chapter2::adaptation::{Adaptation};
+use chapters::chapter2::stopping::{StopCriteria, StopReason};
+use chapters::chapter2::checkpoint::{Checkpoint};
+use chapters::chapter2::islands::{Islands, IslandSettings};
+use chapters::chapter2::interactive::{Interactive, InteractiveSettings};
+use chapters::chapter2::pathfinding::{Pathfinder};
+use chapters::chapter2::selection::{Selection, Replacement};
+use chapters::chapter2::crossover::{Crossover};
+use chapters::chapter2::evaluation::{EvaluationFn};
+use chapters::chapter2::phenotype::{Seed};
+use chapters::chapter2::statistics::{Statistic, Generation};
+use chapters::chapter2::output::{prepare_directory, write_winner, write_history, write_thumbnail, LogFormat};
+use chapters::chapter2::ascii_map::{AsciiMap};
+use chapters::chapter2::tiled::{TiledExport};
+
+use util::spritesheet::{SpriteSheet};
+use util::sprite::{Sprite};
+use util::text::{render_text};
+use util::config::{Config, ConfigError, ConfigErrors};
+use util::chapter::{Chapter, ChapterCallback, GenerateOptions};
+use util::offscreen::{OffscreenSpriteSheet};
+use util::sweep::{RunSummary};
+use util::util::{derive_rng};
+
+static FRAME: AtomicUsize = ATOMIC_USIZE_INIT;
+static CHOICE: AtomicIsize = ATOMIC_ISIZE_INIT;
+static OVERLAY: AtomicBool = ATOMIC_BOOL_INIT; // show the entrance-to-exit path
+
+/// the seed for a run: either `[mu-lambda] seed` or a fresh random
+/// one, which is printed so that the run can be reproduced.
+fn run_seed(config: &Config) -> Result<u64, ConfigError> {
+    let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+    if mulambda_vars.contains("seed") {
+        return config.get_integer(&mulambda_vars, "seed").map(|s| s as u64);
+    }
+    let rng_seed = thread_rng().gen();
+    println!("seed: {}", rng_seed);
+    Ok(rng_seed)
+}
+
+/// where the configured spritesheet image lives.
+fn spritesheet_location(config: &Config) -> Result<&str, ConfigError> {
+    let vars = try!(config.get_table(None, "main"));
+    let spritesheet_name = try!(config.get_string(&vars, "spritesheet"));
+    let spritesheets = try!(config.get_table(None, "spritesheets"));
+    let spritesheet_config = try!(config.get_table(Some(&spritesheets), spritesheet_name));
+    config.get_string(&spritesheet_config, "path")
+}
+
+/// the size of a tile on screen.
+fn tile_size(config: &Config) -> Result<(u32, u32), ConfigErrors> {
+    let mut errors = ConfigErrors::new();
+    let vars = try!(config.get_table(None, "main"));
+    let tile_width = errors.check(config.get_integer(&vars, "tile_width"));
+    let tile_height = errors.check(config.get_integer(&vars, "tile_height"));
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok((tile_width.unwrap() as u32, tile_height.unwrap() as u32))
+}
+
+/// build the phenotype seed (dungeon size and cell options).
+fn load_seed(config: &Config) -> Result<Seed, ConfigErrors> {
+    let mut errors = ConfigErrors::new();
+    let vars = try!(config.get_table(None, "main"));
+    let tiles_width = errors.check(config.get_default(&vars, "tiles_width", 50));
+    let tiles_height = errors.check(config.get_default(&vars, "tiles_height", 50));
+
+    let spritesheet_name = try!(config.get_string(&vars, "spritesheet"));
+    let spritesheets = try!(config.get_table(None, "spritesheets"));
+    let spritesheet_config = try!(config.get_table(Some(&spritesheets), spritesheet_name));
+
+    let cell_data = try!(config.get_table(Some(&spritesheet_config), "cells"));
+    let tiles: Option<Vec<String>> = errors.check(config.get_array(&cell_data, "tiles"));
+    let items: Option<Vec<String>> = errors.check(config.get_array(&cell_data, "items"));
+    let occupants: Option<Vec<String>> = errors.check(config.get_array(&cell_data, "occupants"));
+    let occupant_chance = errors.check(config.get_float(&spritesheet_config, "occupant_chance"));
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let cell_tiles: CellOptions<Tile> = CellOptions::new(&tiles.unwrap()[..]);
+    let cell_items: CellOptions<Item> = CellOptions::new(&items.unwrap()[..]);
+    let cell_occupants: CellOptions<Occupant> = CellOptions::new(&occupants.unwrap()[..]);
+    Ok(Seed::new(tiles_width.unwrap(), tiles_height.unwrap(), cell_tiles, cell_items, cell_occupants, occupant_chance.unwrap()))
+}
+
+/// the configured evaluation functions, by name, with their weights
+/// and whether they are hard constraints. each one is built from its
+/// `[evaluations.<name>]` table, whose optional `type` names the
+/// evaluation to use (the name by default), so that one evaluation
+/// can be used twice with different settings, and whose optional
+/// `hard` (default false) marks a constraint for FI-2Pop.
+fn load_evaluations(config: &Config, registry: &EvaluationRegistry, seed: &Seed) -> Result<Vec<(String, EvaluationFn, f64, bool)>, ConfigErrors> {
+    let mut errors = ConfigErrors::new();
+    let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+    let evaluations: Option<Vec<String>> = errors.check(config.get_array(&mulambda_vars, "evaluations"));
+    let evaluation_weights: Option<Vec<f64>> = errors.check(config.get_array(&mulambda_vars, "evaluation_weights"));
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let (evaluations, evaluation_weights) = (evaluations.unwrap(), evaluation_weights.unwrap());
+    if evaluations.len() != evaluation_weights.len() {
+        let why = format!("has {} weights, but there are {} evaluations.", evaluation_weights.len(), evaluations.len());
+        errors.push(ConfigError::invalid(mulambda_vars.key("evaluation_weights"), why));
+    }
+    let evaluations_vars = try!(config.get_optional_table(None, "evaluations"));
+    let mut result = vec![];
+    for (index, (eval, weight)) in evaluations.into_iter().zip(evaluation_weights.into_iter()).enumerate() {
+        let vars = try!(config.get_optional_table(Some(&evaluations_vars), &eval[..]));
+        let hard = match errors.check(config.get_default(&vars, "hard", false)) {
+            Some(hard) => hard,
+            None => continue
+        };
+        let kind = if vars.contains("type") {
+            match errors.check(config.get_string(&vars, "type")) {
+                Some(kind) => kind,
+                None => continue
+            }
+        } else {
+            &eval[..]
+        };
+        match registry.create(kind, config, &vars, seed) {
+            Ok(evaluation_fn) => {
+                if let Some(evaluation_fn) = errors.merge(evaluation_fn) {
+                    result.push((eval.clone(), evaluation_fn, weight, hard));
+                }
+            }
+            Err(why) => {
+                let key = if vars.contains("type") {
+                    vars.key("type")
+                } else {
+                    format!("{}[{}]", mulambda_vars.key("evaluations"), index)
+                };
+                errors.push(ConfigError::invalid(key, why));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(result)
+}
+
+/// what runs the evolution, as named by `[mu-lambda] engine`.
+#[derive(Clone, Debug, PartialEq)]
+enum Engine {
+    MuLambda, // ranks on the weighted sum of the evaluations
+    Nsga2,    // ranks on the evaluation scores; returns the Pareto front
+    Fi2Pop,   // separate populations for dungeons that meet the hard evaluations and those that don't
+    MapElites(Dimension, Dimension), // the best per bin of two descriptors
+}
+
+impl Engine {
+    /// the name `[mu-lambda] engine` gives it.
+    fn name(&self) -> &'static str {
+        match *self {
+            Engine::MuLambda => "mu-lambda",
+            Engine::Nsga2 => "nsga2",
+            Engine::Fi2Pop => "fi-2pop",
+            Engine::MapElites(..) => "map-elites",
+        }
+    }
+}
+
+/// parameters of the evolutionary run.
+struct MuLambdaSettings {
+    engine: Engine,
+    threads: usize,
+    chunk_size: usize, // dungeons per job for the worker threads (0 for an even share)
+    iterations: u32,
+    mu: usize,
+    lambda: usize,
+    mutation: f64,
+    adaptation: Adaptation,
+    selection: Selection,
+    replacement: Replacement,
+    crossover_rate: f64,
+    crossover: Crossover,
+    stopping: StopCriteria,
+    checkpoint: Option<(String, u32)>, // where to save, and every how many iterations
+    resume: Option<String>, // the checkpoint to carry on from
+    cache_size: usize, // genotypes whose statistics are remembered (0 for none)
+    strategy: String,
+}
+
+fn load_mu_lambda(config: &Config) -> Result<MuLambdaSettings, ConfigErrors> {
+    let mut errors = ConfigErrors::new();
+    let vars = try!(config.get_table(None, "main"));
+    let threads = errors.check(config.get_default(&vars, "threads", get()));
+    let chunk_size = errors.check(config.get_default(&vars, "chunk_size", 0));
+    if let Some(0) = threads {
+        errors.push(ConfigError::invalid(vars.key("threads"), "must be at least 1.".to_string()));
+    }
+
+    let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+    let mu = errors.check(config.get_default(&mulambda_vars, "mu", 100));
+    let lambda = errors.check(config.get_default(&mulambda_vars, "lambda", 100));
+    let mutation = errors.check(config.get_default(&mulambda_vars, "mutation", 0.33));
+    let iterations = errors.check(config.get_default(&mulambda_vars, "iterations", 100));
+    if let Some(0) = iterations {
+        errors.push(ConfigError::invalid(mulambda_vars.key("iterations"), "must be at least 1.".to_string()));
+    }
+    if let Some(0) = mu {
+        errors.push(ConfigError::invalid(mulambda_vars.key("mu"), "must be at least 1.".to_string()));
+    }
+    if let Some(0) = lambda {
+        errors.push(ConfigError::invalid(mulambda_vars.key("lambda"), "must be at least 1.".to_string()));
+    }
+    if mulambda_vars.contains("seed") {
+        errors.check(config.get_integer(&mulambda_vars, "seed"));
+    }
+    let engine = match errors.check(config.get_default(&mulambda_vars, "engine", "mu-lambda".to_string())) {
+        Some(ref name) if name == "mu-lambda" => Some(Engine::MuLambda),
+        Some(ref name) if name == "nsga2" => Some(Engine::Nsga2),
+        Some(ref name) if name == "fi-2pop" => Some(Engine::Fi2Pop),
+        Some(ref name) if name == "map-elites" => {
+            let map_vars = try!(config.get_table(None, "map-elites"));
+            let x = errors.check(config.get_table(Some(&map_vars), "x")).and_then(|vars| errors.merge(Dimension::from_config(config, &vars)));
+            let y = errors.check(config.get_table(Some(&map_vars), "y")).and_then(|vars| errors.merge(Dimension::from_config(config, &vars)));
+            match (x, y) {
+                (Some(x), Some(y)) => Some(Engine::MapElites(x, y)),
+                _ => None
+            }
+        }
+        Some(name) => {
+            let why = format!("unknown engine `{}` (expected mu-lambda, nsga2, fi-2pop or map-elites).", name);
+            errors.push(ConfigError::invalid(mulambda_vars.key("engine"), why));
+            None
+        }
+        None => None
+    };
+    let adaptation = errors.merge(Adaptation::from_config(config, &mulambda_vars));
+    let selection = errors.merge(Selection::from_config(config, &mulambda_vars));
+    let replacement = errors.merge(Replacement::from_config(config, &mulambda_vars));
+    let crossover_rate = errors.check(config.get_default(&mulambda_vars, "crossover_rate", 0.0));
+    let crossover = errors.merge(Crossover::from_config(config, &mulambda_vars));
+    let stopping = errors.merge(StopCriteria::from_config(config, &mulambda_vars));
+    let optional_path = |errors: &mut ConfigErrors, key: &str| {
+        if mulambda_vars.contains(key) {
+            errors.check(config.get_string(&mulambda_vars, key)).map(|path| Some(path.to_string()))
+        } else {
+            Some(None)
+        }
+    };
+    let checkpoint = optional_path(&mut errors, "checkpoint");
+    let resume = optional_path(&mut errors, "resume");
+    let cache_size = errors.check(config.get_default(&mulambda_vars, "cache_size", 0));
+    let checkpoint_every = errors.check(config.get_default(&mulambda_vars, "checkpoint_every", 10));
+    if let Some(0) = checkpoint_every {
+        errors.push(ConfigError::invalid(mulambda_vars.key("checkpoint_every"), "must be at least 1.".to_string()));
+    }
+    if let Some(mutation) = mutation {
+        if mutation < 0.0 || mutation > 1.0 {
+            let why = format!("must be between 0.0 and 1.0, not {}.", mutation);
+            errors.push(ConfigError::invalid(mulambda_vars.key("mutation"), why));
+        }
+    }
+    if let Some(rate) = crossover_rate {
+        if rate < 0.0 || rate > 1.0 {
+            let why = format!("must be between 0.0 and 1.0, not {}.", rate);
+            errors.push(ConfigError::invalid(mulambda_vars.key("crossover_rate"), why));
+        }
+    }
+    let strategy = errors.check(config.get_string(&mulambda_vars, "strategy"));
+    if let (Some(mu), Some(lambda), Some(&Replacement::Comma)) = (mu, lambda, replacement.as_ref()) {
+        if lambda < mu {
+            let why = format!("must be at least mu ({}) for comma replacement.", mu);
+            errors.push(ConfigError::invalid(mulambda_vars.key("lambda"), why));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(MuLambdaSettings {
+        engine: engine.unwrap(),
+        threads: threads.unwrap(),
+        chunk_size: chunk_size.unwrap(),
+        iterations: iterations.unwrap(),
+        mu: mu.unwrap(),
+        lambda: lambda.unwrap(),
+        mutation: mutation.unwrap(),
+        adaptation: adaptation.unwrap(),
+        selection: selection.unwrap(),
+        replacement: replacement.unwrap(),
+        crossover_rate: crossover_rate.unwrap(),
+        crossover: crossover.unwrap(),
+        stopping: stopping.unwrap(),
+        checkpoint: checkpoint.unwrap().map(|path| (path, checkpoint_every.unwrap())),
+        resume: resume.unwrap(),
+        cache_size: cache_size.unwrap(),
+        strategy: strategy.unwrap().to_string(),
+    })
+}
+
+/// construct the genotype named by `[mu-lambda] strategy`.
+fn create_genotype(config: &Config, genotypes: &GenotypeRegistry, strategy: &str, seed: &Seed) -> Result<Box<AnyGenotype>, ConfigErrors> {
+    match genotypes.create(strategy, config, seed) {
+        Ok(genotype) => genotype,
+        Err(why) => {
+            let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+            Err(ConfigError::invalid(mulambda_vars.key("strategy"), why).into())
+        }
+    }
+}
+
+/// the `[novelty]` settings, if enabled. novelty search only ranks
+/// the plain `mu-lambda` engine.
+fn load_novelty(config: &Config, seed: &Seed, settings: &MuLambdaSettings) -> Result<Option<Novelty>, ConfigErrors> {
+    let novelty = try!(Novelty::from_config(config, seed, settings.lambda));
+    match (novelty.is_some(), &settings.engine) {
+        (true, &Engine::MuLambda) | (false, _) => Ok(novelty),
+        (true, _) => {
+            let vars = try!(config.get_optional_table(None, "novelty"));
+            let why = "novelty search needs the mu-lambda engine.".to_string();
+            Err(ConfigError::invalid(vars.key("enabled"), why).into())
+        }
+    }
+}
+
+/// which evaluations are hard constraints. FI-2Pop needs at least one.
+fn load_constraints(config: &Config, engine: &Engine, evaluations: &[(String, EvaluationFn, f64, bool)]) -> Result<Vec<bool>, ConfigError> {
+    let hard: Vec<bool> = evaluations.iter().map(|&(_, _, _, hard)| hard).collect();
+    if *engine == Engine::Fi2Pop && !hard.iter().any(|&h| h) {
+        let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+        let why = "fi-2pop needs at least one evaluation with `hard = true`.".to_string();
+        return Err(ConfigError::invalid(mulambda_vars.key("engine"), why));
+    }
+    Ok(hard)
+}
+
+/// the checkpoint named by `[mu-lambda] resume`.
+fn load_checkpoint(config: &Config, path: &str) -> Result<Checkpoint, ConfigErrors> {
+    let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+    Checkpoint::load(Path::new(path)).map_err(|why| ConfigError::invalid(mulambda_vars.key("resume"), why).into())
+}
+
+/// how a run's statistics are reported, from `[statistics]`.
+struct StatisticsSettings {
+    format: LogFormat,
+    progress: bool, // print a line per generation
+    path: Option<String>, // where an interactive run writes them, if anywhere
+}
+
+fn load_statistics(config: &Config) -> Result<StatisticsSettings, ConfigErrors> {
+    let mut errors = ConfigErrors::new();
+    let vars = try!(config.get_optional_table(None, "statistics"));
+    let format = match errors.check(config.get_default(&vars, "format", "csv".to_string())) {
+        Some(ref name) if name == "csv" => Some(LogFormat::Csv),
+        Some(ref name) if name == "json" => Some(LogFormat::Json),
+        Some(name) => {
+            let why = format!("unknown format `{}` (expected csv or json).", name);
+            errors.push(ConfigError::invalid(vars.key("format"), why));
+            None
+        }
+        None => None
+    };
+    let progress = errors.check(config.get_default(&vars, "progress", false));
+    let path = if vars.contains("path") {
+        errors.check(config.get_string(&vars, "path")).map(|path| Some(path.to_string()))
+    } else {
+        Some(None)
+    };
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(StatisticsSettings {
+        format: format.unwrap(),
+        progress: progress.unwrap(),
+        path: path.unwrap(),
+    })
+}
+
+/// where MAP-Elites winners sit in the archive: row by row, an index
+/// into the winners (or none for an empty bin).
+struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<usize>>,
+}
+
+/// what a run produced.
+struct Outcome {
+    winners: Vec<(Dungeon, Statistic)>,
+    grid: Option<Grid>,
+    evaluations: Vec<String>, // the names of the evaluations, in order
+    histories: Vec<Vec<Generation>>, // one per island (usually just the one)
+    stopped: StopReason,
+}
+
+impl Outcome {
+    /// how many iterations ran (on the island that ran longest).
+    fn iterations(&self) -> usize {
+        self.histories.iter().map(|history| history.len()).max().unwrap_or(0)
+    }
+}
+
+/// where island `island` of `islands` writes its history: `path`
+/// itself for a single population, otherwise with `-island<N>` added
+/// to its name.
+fn island_path(path: &Path, island: usize, islands: usize) -> PathBuf {
+    if islands == 1 {
+        return path.to_path_buf();
+    }
+    suffixed_path(path, &format!("island{}", island))
+}
+
+/// `path` with `-<suffix>` added before its extension.
+fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("");
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => path.with_file_name(format!("{}-{}.{}", stem, suffix, extension)),
+        None => path.with_file_name(format!("{}-{}", stem, suffix))
+    }
+}
+
+/// the dungeon an individual was evaluated on (generated again only
+/// if it was not kept), along with its statistic.
+fn phenotype<G: Genotype>(individual: G, mut statistic: Statistic) -> (Dungeon, Statistic) {
+    let dungeon = match statistic.phenotype.take() {
+        Some(dungeon) => dungeon,
+        None => individual.generate()
+    };
+    (dungeon, statistic)
+}
+
+/// a configured `MuLambda` (not yet run), along with the engine to run
+/// it with, which evaluations are hard constraints, and their names.
+fn build(config: &Config, registry: &Registry, rng_seed: u64, progress: bool)
+         -> Result<(Engine, MuLambda<Box<AnyGenotype>>, Vec<bool>, Vec<String>), ConfigErrors> {
+
+    // load a whole bunch of configurable options
+    let settings = try!(load_mu_lambda(config));
+    let seed = try!(load_seed(config));
+    let evaluations = try!(load_evaluations(config, &registry.evaluations, &seed));
+    let hard = try!(load_constraints(config, &settings.engine, &evaluations[..]));
+    let names: Vec<String> = evaluations.iter().map(|&(ref name, _, _, _)| name.clone()).collect();
+    let (evaluation_fns, evaluation_weights): (Vec<EvaluationFn>, Vec<f64>) = evaluations
+        .into_iter()
+        .map(|(_, evaluation_fn, weight, _)| (evaluation_fn, weight))
+        .unzip();
+
+    let genotype = try!(create_genotype(config, &registry.genotypes, &settings.strategy[..], &seed));
+    let novelty = try!(load_novelty(config, &seed, &settings));
+
+    let mut mulambda = MuLambda::new(settings.threads,
+                                     settings.chunk_size,
+                                     settings.iterations,
+                                     settings.mu,
+                                     settings.lambda,
+                                     settings.mutation,
+                                     settings.adaptation,
+                                     settings.selection,
+                                     settings.replacement,
+                                     settings.crossover_rate,
+                                     settings.crossover,
+                                     genotype,
+                                     evaluation_fns,
+                                     evaluation_weights,
+                                     rng_seed);
+    if let Some(novelty) = novelty {
+        mulambda.set_novelty(novelty);
+    }
+    mulambda.set_progress(progress);
+    mulambda.set_run_kind(settings.engine.name(), &settings.strategy[..]);
+    mulambda.set_stopping(settings.stopping);
+    if settings.cache_size > 0 {
+        mulambda.set_cache(settings.cache_size);
+    }
+    if let Some((ref path, every)) = settings.checkpoint {
+        mulambda.set_checkpoint(PathBuf::from(path), every);
+    }
+    if let Some(ref path) = settings.resume {
+        let checkpoint = try!(load_checkpoint(config, &path[..]));
+        if let Err(why) = mulambda.resume(checkpoint) {
+            let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+            let why = format!("does not fit this run: {}", why);
+            return Err(ConfigError::invalid(mulambda_vars.key("resume"), why).into());
+        }
+    }
+    Ok((settings.engine, mulambda, hard, names))
+}
+
+/// run the configured evolutionary strategy and return every winner
+/// along with its statistic. nothing graphical is touched here.
+fn evolve(config: &Config, registry: &Registry, rng_seed: u64) -> Result<Outcome, ConfigErrors> {
+    let statistics = try!(load_statistics(config));
+    if let Some((islands, configs)) = try!(load_islands(config, registry)) {
+        return evolve_islands(config, registry, rng_seed, islands, configs, statistics.progress);
+    }
+    let (engine, mut mulambda, hard, names) = try!(build(config, registry, rng_seed, statistics.progress));
+    let (result, history, stopped) = match engine {
+        Engine::MuLambda => (mulambda.run(), mulambda.history().to_vec(), mulambda.stop_reason()),
+        Engine::Nsga2 => {
+            let mut nsga2 = Nsga2::new(mulambda);
+            (nsga2.run(), nsga2.history().to_vec(), nsga2.stop_reason())
+        }
+        Engine::Fi2Pop => {
+            let mut fi2pop = Fi2Pop::new(mulambda, hard);
+            let populations = fi2pop.run();
+            let result = if populations.feasible.is_empty() {
+                println!("No feasible dungeon was found; showing the least infeasible ones.");
+                populations.infeasible
+            } else {
+                populations.feasible
+            };
+            (result, fi2pop.history().to_vec(), fi2pop.stop_reason())
+        }
+        Engine::MapElites(x, y) => {
+            let mut map_elites = MapElites::new(mulambda, x, y);
+            let archive = map_elites.run();
+            let mut winners = vec![];
+            let cells = archive.cells.into_iter().map(|cell| {
+                cell.map(|(individual, statistic)| {
+                    winners.push(phenotype(individual, statistic));
+                    winners.len() - 1
+                })
+            }).collect();
+            return Ok(Outcome {
+                winners: winners,
+                grid: Some(Grid {
+                    width: archive.width,
+                    height: archive.height,
+                    cells: cells,
+                }),
+                evaluations: names,
+                histories: vec![map_elites.history().to_vec()],
+                stopped: map_elites.stop_reason(),
+            });
+        }
+    };
+    Ok(Outcome {
+        winners: result.into_iter().map(|(individual, statistic)| phenotype(individual, statistic)).collect(),
+        grid: None,
+        evaluations: names,
+        histories: vec![history],
+        stopped: stopped,
+    })
+}
+
+/// the `[islands]` settings and every island's configuration, if there
+/// is more than one island. islands only run the plain `mu-lambda`
+/// engine, without checkpoints, and must share their evaluations
+/// (though not necessarily their weights).
+fn load_islands(config: &Config, registry: &Registry) -> Result<Option<(IslandSettings, Vec<Config>)>, ConfigErrors> {
+    let settings = match try!(IslandSettings::from_config(config)) {
+        Some(settings) => settings,
+        None => return Ok(None)
+    };
+    let mut errors = ConfigErrors::new();
+    let names: Option<Vec<String>> = errors.merge(load_seed(config))
+        .and_then(|seed| errors.merge(load_evaluations(config, &registry.evaluations, &seed)))
+        .map(|evaluations| evaluations.into_iter().map(|(name, _, _, _)| name).collect());
+    let vars = try!(config.get_optional_table(None, "islands"));
+    let mut configs = vec![];
+    for island in 0..settings.count {
+        let island_config = match errors.merge(IslandSettings::island_config(config, island)) {
+            Some(island_config) => island_config,
+            None => continue
+        };
+        let key = vars.key(&island.to_string()[..]);
+        let seed = errors.merge(load_seed(&island_config));
+        if let Some(island_settings) = errors.merge(load_mu_lambda(&island_config)) {
+            if island_settings.engine != Engine::MuLambda {
+                errors.push(ConfigError::invalid(key.clone(), "islands need the mu-lambda engine.".to_string()));
+            }
+            if island_settings.checkpoint.is_some() || island_settings.resume.is_some() {
+                errors.push(ConfigError::invalid(key.clone(), "islands cannot be checkpointed.".to_string()));
+            }
+            if let Some(ref seed) = seed {
+                errors.merge(create_genotype(&island_config, &registry.genotypes, &island_settings.strategy[..], seed));
+                errors.merge(load_novelty(&island_config, seed, &island_settings));
+            }
+        }
+        let evaluations = seed.and_then(|seed| errors.merge(load_evaluations(&island_config, &registry.evaluations, &seed)));
+        if let Some(evaluations) = evaluations {
+            let island_names: Vec<String> = evaluations.into_iter().map(|(name, _, _, _)| name).collect();
+            if names.as_ref().map_or(false, |names| *names != island_names) {
+                let why = format!("has evaluations {} instead of {}.", island_names.join(", "), names.as_ref().unwrap().join(", "));
+                errors.push(ConfigError::invalid(key, why));
+            }
+        }
+        configs.push(island_config);
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(Some((settings, configs)))
+}
+
+/// run the island model. the winners of every island are merged and
+/// ranked on the main configuration's weights, so that islands that
+/// weigh evaluations differently can be compared.
+fn evolve_islands(config: &Config, registry: &Registry, rng_seed: u64, settings: IslandSettings, configs: Vec<Config>, progress: bool) -> Result<Outcome, ConfigErrors> {
+    let evaluations = try!(load_evaluations(config, &registry.evaluations, &try!(load_seed(config))));
+    let names: Vec<String> = evaluations.iter().map(|&(ref name, _, _, _)| name.clone()).collect();
+    let weights: Vec<f64> = evaluations.iter().map(|&(_, _, weight, _)| weight).collect();
+    let mut islands = vec![];
+    for (island, island_config) in configs.iter().enumerate() {
+        let island_seed = derive_rng(rng_seed, &[island as u64]).gen();
+        // only the first island prints progress, so that lines do not interleave.
+        let (_, mulambda, _, _) = try!(build(island_config, registry, island_seed, progress && island == 0));
+        islands.push(mulambda);
+    }
+    let mut model = Islands::new(islands, settings);
+    let populations = model.run();
+    let mut winners: Vec<(Dungeon, Statistic)> = populations.into_iter().flat_map(|population| population.into_iter()).map(|(individual, mut statistic)| {
+        statistic.fitness = statistic.scores.iter().zip(weights.iter()).fold(0.0, |accum, (score, weight)| accum + score * weight);
+        statistic.ranking = statistic.fitness;
+        phenotype(individual, statistic)
+    }).collect();
+    winners.sort_by(|&(_, ref a), &(_, ref b)| a.fitness.partial_cmp(&b.fitness).unwrap_or(cmp::Ordering::Equal));
+    Ok(Outcome {
+        winners: winners,
+        grid: None,
+        evaluations: names,
+        histories: model.histories(),
+        stopped: model.stop_reason(),
+    })
+}
+
+/// check the whole configuration, collecting every problem rather
+/// than stopping at the first one.
+pub fn chapter2_check(config: &Config, registry: &Registry) -> Result<(), ConfigErrors> {
+    let genotypes = &registry.genotypes;
+    let mut errors = ConfigErrors::new();
+    errors.merge(tile_size(config));
+    errors.check(spritesheet_location(config));
+    let settings = errors.merge(load_mu_lambda(config));
+    errors.merge(load_statistics(config));
+    if let Some(&MuLambdaSettings { resume: Some(ref path), .. }) = settings.as_ref() {
+        errors.merge(load_checkpoint(config, &path[..]));
+    }
+    errors.merge(load_islands(config, registry));
+    errors.merge(load_interactive(config));
+    if let Some(seed) = errors.merge(load_seed(config)) {
+        errors.merge(load_pathfinder(config, &seed));
+        let evaluations = errors.merge(load_evaluations(config, &registry.evaluations, &seed));
+        if let (Some(settings), Some(evaluations)) = (settings.as_ref(), evaluations.as_ref()) {
+            errors.check(load_constraints(config, &settings.engine, &evaluations[..]));
+        }
+        // check every `[evaluations.<name>]` table, even unused ones.
+        if let Some(evaluations_vars) = errors.check(config.get_optional_table(None, "evaluations")) {
+            for name in config.get_listing(&evaluations_vars, vec![]) {
+                if let Some(vars) = errors.check(config.get_table(Some(&evaluations_vars), &name[..])) {
+                    let kind = match vars.get("type").and_then(|kind| kind.as_str()) {
+                        Some(kind) => kind,
+                        None => &name[..]
+                    };
+                    if let Ok(evaluation_fn) = registry.evaluations.create(kind, config, &vars, &seed) {
+                        errors.merge(evaluation_fn);
+                    }
+                }
+            }
+        }
+        errors.merge(AsciiMap::new(config, &seed));
+        if let Some(ref settings) = settings {
+            errors.merge(load_novelty(config, &seed, settings));
+        }
+        let strategy = settings.map(|s| s.strategy);
+        if let Some(ref strategy) = strategy {
+            errors.merge(create_genotype(config, genotypes, &strategy[..], &seed));
+        }
+        // check every genotype that has a section, not only the current one.
+        let root = config.root();
+        for (name, section) in genotypes.sections() {
+            if root.contains(section) && Some(name) != strategy.as_ref().map(|s| &s[..]) {
+                if let Ok(genotype) = genotypes.create(name, config, &seed) {
+                    errors.merge(genotype);
+                }
+            }
+        }
+    }
+    errors.into_result()
+}
+
+/// headless batch mode: run the strategy `count` times and write
+/// every winner to `out` as `run<N>-dungeon<M>.json` and an ASCII map
+/// `run<N>-dungeon<M>.txt`. run N uses the run seed plus N. if a
+/// thumbnail format (e.g. "png") is given, every winner is rendered
+/// to an image as well. with `tmx`, winners are also exported as
+/// Tiled maps sharing one tileset. a summary of every generation goes
+/// to `run<N>-statistics.csv` (or `.json`, see `[statistics]`). with
+/// more than one run, each is checkpointed to its own file (the
+/// checkpoint path with `-run<N>` added), and none can be resumed.
+pub fn chapter2_generate(config: &Config, registry: &Registry, options: &GenerateOptions) -> Result<(), ConfigErrors> {
+    let settings = try!(load_mu_lambda(config));
+    if options.count > 1 && settings.resume.is_some() {
+        let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+        let why = format!("cannot carry on {} runs from one checkpoint.", options.count);
+        return Err(ConfigError::invalid(mulambda_vars.key("resume"), why).into());
+    }
+    let out = &options.out;
+    let thumbnails = options.thumbnails.as_ref().map(|t| &t[..]);
+    let ascii_map = try!(AsciiMap::new(config, &try!(load_seed(config))));
+    let (tile_width, tile_height) = try!(tile_size(config));
+    let spritesheet_path = Path::new(try!(spritesheet_location(config)));
+    let rng_seed = try!(run_seed(config));
+    let statistics = try!(load_statistics(config));
+    prepare_directory(out);
+    let spritesheet = thumbnails.map(|_| OffscreenSpriteSheet::new(spritesheet_path));
+    let tiled = if options.tmx {
+        // match the viewer's animation speed
+        let vars = try!(config.get_table(None, "main"));
+        let animation_speed = try!(config.get_default(&vars, "animation_speed", 10));
+        let fps = try!(config.get_default(&vars, "fps", 10));
+        let export = TiledExport::new(spritesheet_path, animation_speed * 1000 / fps);
+        export.write_tileset(out);
+        Some(export)
+    } else {
+        None
+    };
+    for run in 0..options.count {
+        let mut run_config = config.clone();
+        if let (true, Some((ref path, _))) = (options.count > 1, settings.checkpoint.as_ref()) {
+            let path = suffixed_path(Path::new(path), &format!("run{}", run));
+            let path = Value::String(path.to_string_lossy().into_owned());
+            try!(run_config.set("mu-lambda.checkpoint", path));
+        }
+        let outcome = try!(evolve(&run_config, registry, rng_seed.wrapping_add(run as u64)));
+        let path = out.join(format!("run{}-statistics.{}", run, statistics.format.extension()));
+        for (island, history) in outcome.histories.iter().enumerate() {
+            let path = island_path(&path, island, outcome.histories.len());
+            write_history(&path, &statistics.format, &outcome.evaluations[..], &history[..], &outcome.stopped);
+        }
+        println!("run {}: stopped after {} iterations ({})", run, outcome.iterations(), outcome.stopped);
+        let winners = outcome.winners;
+        for (index, &(ref dungeon, ref statistic)) in winners.iter().enumerate() {
+            let name = format!("run{}-dungeon{}", run, index);
+            write_winner(&out.join(format!("{}.json", name)), dungeon, statistic);
+            ascii_map.save(&out.join(format!("{}.txt", name)), dungeon);
+            if let (Some(extension), Some(sheet)) = (thumbnails, spritesheet.as_ref()) {
+                let path = out.join(format!("{}.{}", name, extension));
+                write_thumbnail(&path, sheet, dungeon, tile_width, tile_height);
+            }
+            if let Some(ref export) = tiled {
+                export.write_map(&out.join(format!("{}.tmx", name)), dungeon);
+            }
+        }
+        println!("run {}: wrote {} dungeons to {}", run, winners.len(), out.display());
+    }
+    Ok(())
+}
+
+/// run once and summarise the final population's fitness. as every
+/// run of a sweep would share them, checkpoints are not allowed.
+pub fn chapter2_summarize(config: &Config, registry: &Registry) -> Result<RunSummary, ConfigErrors> {
+    let settings = try!(load_mu_lambda(config));
+    let mulambda_vars = try!(config.get_table(None, "mu-lambda"));
+    let mut errors = ConfigErrors::new();
+    if settings.checkpoint.is_some() {
+        errors.push(ConfigError::invalid(mulambda_vars.key("checkpoint"), "cannot be used in a sweep.".to_string()));
+    }
+    if settings.resume.is_some() {
+        errors.push(ConfigError::invalid(mulambda_vars.key("resume"), "cannot be used in a sweep.".to_string()));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let winners = try!(evolve(config, registry, try!(run_seed(config)))).winners;
+    let fitnesses: Vec<f64> = winners.iter().map(|&(_, ref statistic)| statistic.fitness).collect();
+    let best = fitnesses.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mean = fitnesses.iter().fold(0.0, |accum, &f| accum + f) / fitnesses.len() as f64;
+    Ok(RunSummary {
+        best: best,
+        mean: mean,
+    })
+}
+
+/// score hand-drawn (or previously exported) ASCII maps against the
+/// configured evaluation functions. a map that cannot be loaded is
+/// reported and skipped; returns how many were.
+pub fn chapter2_evaluate(config: &Config, registry: &Registry, maps: &[String]) -> Result<usize, ConfigErrors> {
+    let seed = try!(load_seed(config));
+    let ascii_map = try!(AsciiMap::new(config, &seed));
+    let evaluations = try!(load_evaluations(config, &registry.evaluations, &seed));
+    let mut skipped = 0;
+    for map in maps {
+        let dungeon = match ascii_map.load(Path::new(&map[..])) {
+            Ok(d) => d,
+            Err(why) => {
+                let _ = writeln!(&mut stderr(), "Could not load map {}", why);
+                skipped += 1;
+                continue;
+            }
+        };
+        println!("{}:", map);
+        let mut total = 0.0;
+        for &(ref name, ref evaluation_fn, weight, hard) in evaluations.iter() {
+            let score = evaluation_fn(&dungeon);
+            total += weight * score;
+            println!("  {:<24} {:>8.2} (x {}{})", name, score, weight, if hard { ", hard" } else { "" });
+        }
+        println!("  {:<24} {:>8.2}", "fitness", total);
+    }
+    Ok(skipped)
+}
+
+/// draw a dungeon with its top left corner at (x, y), its tiles
+/// `scale` times their size.
+fn draw_dungeon(gl: &mut GlGraphics, viewport: Viewport, spritesheet: &SpriteSheet, dungeon: &Dungeon,
+                (x, y): (f64, f64), (tile_width, tile_height): (i32, i32), scale: f64, seconds: usize) {
+    let (width, height) = (tile_width as f64 * scale, tile_height as f64 * scale);
+    for cell in DungeonCells::new(dungeon) {
+        let cx = x + cell.x as f64 * width;
+        let cy = y + cell.y as f64 * height;
+        match cell.tile {
+            Some(ref val) => {
+                let sprite = spritesheet.sprites.get(&val.name()).unwrap();
+                sprite.draw_scaled(gl, viewport, cx, cy, scale, seconds);
+            }
+            None => {
+                Sprite::missing(gl, viewport, cx as i32, cy as i32, width as i32, height as i32);
+            }
+        }
+        match cell.occupant {
+            Some(ref val) => {
+                let sprite = spritesheet.sprites.get(&val.name()).unwrap();
+                sprite.draw_scaled(gl, viewport, cx, cy, scale, seconds);
+            }
+            None => ()
+        }
+    }
+}
+
+/// draw a path as a line through the centres of its cells.
+fn draw_path(gl: &mut GlGraphics, viewport: Viewport, path: &[(u32, u32)], (tile_width, tile_height): (i32, i32)) {
+    let line = Line::new([0.0, 1.0, 1.0, 1.0], 1.5);
+    let centre = |(x, y): (u32, u32)| ((x as f64 + 0.5) * tile_width as f64, (y as f64 + 0.5) * tile_height as f64);
+    gl.draw(viewport, |c, gl| {
+        for step in path.windows(2) {
+            let ((x1, y1), (x2, y2)) = (centre(step[0]), centre(step[1]));
+            line.draw([x1, y1, x2, y2], default_draw_state(), c.transform, gl);
+        }
+    });
+}
+
+/// the `[pathfinding]` settings, with which the viewer finds the path
+/// it overlays.
+fn load_pathfinder(config: &Config, seed: &Seed) -> Result<Pathfinder, ConfigErrors> {
+    let vars = try!(config.get_optional_table(None, "pathfinding"));
+    Pathfinder::from_config(config, &vars, seed)
+}
+
+/// the `[interactive]` settings, if enabled. interactive evolution
+/// drives a single `mu-lambda` population.
+fn load_interactive(config: &Config) -> Result<Option<InteractiveSettings>, ConfigErrors> {
+    let settings = try!(InteractiveSettings::from_config(config));
+    if settings.is_some() {
+        let vars = try!(config.get_optional_table(None, "interactive"));
+        if try!(load_mu_lambda(config)).engine != Engine::MuLambda {
+            return Err(ConfigError::invalid(vars.key("enabled"), "interactive evolution needs the mu-lambda engine.".to_string()).into());
+        }
+        if try!(IslandSettings::from_config(config)).is_some() {
+            return Err(ConfigError::invalid(vars.key("enabled"), "interactive evolution needs a single island.".to_string()).into());
+        }
+    }
+    Ok(settings)
+}
+
+/// the viewer's state during interactive evolution.
+struct Designer {
+    interactive: Interactive<Box<AnyGenotype>>,
+    shown: usize,
+    cursor: usize, // the shown dungeon under the cursor
+    picks: Vec<usize>,
+    mouse: [f64; 2], // where the mouse is, in pixels
+    accepted: Option<(Dungeon, Statistic)>,
+}
+
+impl Designer {
+    fn toggle(&mut self, index: usize) {
+        match self.picks.iter().position(|&pick| pick == index) {
+            Some(position) => { self.picks.remove(position); }
+            None => self.picks.push(index)
+        }
+    }
+
+    fn breed(&mut self) {
+        let picks = mem::replace(&mut self.picks, vec![]);
+        self.interactive.advance(&picks[..]);
+        self.cursor = 0;
+    }
+
+    fn accept(&mut self, path: Option<&str>) {
+        let (individual, statistic) = self.interactive.shown(self.shown)[self.cursor].clone();
+        let (dungeon, statistic) = phenotype(individual, statistic);
+        println!("Accepted dungeon #{} of generation {} (fitness {}).", self.cursor, self.interactive.generation(), statistic.fitness);
+        if let Some(path) = path {
+            write_winner(Path::new(path), &dungeon, &statistic);
+            println!("Wrote it to {}.", path);
+        }
+        self.accepted = Some((dungeon, statistic));
+    }
+}
+
+/// interactive evolution in the viewer: the best dungeons of every
+/// generation are shown in a grid. the arrow keys (or the mouse) move
+/// between them, space (or a click) picks one, `n` breeds the next
+/// generation from the picks, and enter accepts the dungeon under the
+/// cursor.
+fn chapter2_interactive(config: &Config, registry: &Registry, settings: InteractiveSettings) -> Result<ChapterCallback, ConfigErrors> {
+    let vars = try!(config.get_table(None, "main"));
+    let (tile_width, tile_height) = try!(tile_size(config));
+    let (tile_width, tile_height) = (tile_width as i32, tile_height as i32);
+    let animation_speed = try!(config.get_default(&vars, "animation_speed", 10));
+    let tiles_width: i32 = try!(config.get_default(&vars, "tiles_width", 50));
+    let tiles_height: i32 = try!(config.get_default(&vars, "tiles_height", 50));
+
+    let (_, mulambda, _, _) = try!(build(config, registry, try!(run_seed(config)), false));
+    let spritesheet_path = Path::new(try!(spritesheet_location(config)));
+    let spritesheet = SpriteSheet::new(&spritesheet_path);
+
+    // lay the shown dungeons out in a square grid, scaled to fit.
+    let columns = (settings.shown as f64).sqrt().ceil() as i32;
+    let (cell_width, cell_height) = (tiles_width * tile_width / columns, tiles_height * tile_height / columns);
+    let scale = ((cell_width - 4) as f64 / (tiles_width * tile_width) as f64)
+        .min((cell_height - 4) as f64 / (tiles_height * tile_height) as f64);
+    println!("Pick dungeons with space (or a click), breed the next generation with n, and accept one with enter.");
+
+    let path = settings.path.clone();
+    let designer = RefCell::new(Designer {
+        interactive: Interactive::new(mulambda, settings.preference.clone()),
+        shown: settings.shown,
+        cursor: 0,
+        picks: vec![],
+        mouse: [0.0, 0.0],
+        accepted: None,
+    });
+
+    Ok(box move |gl: &mut GlGraphics, face: &mut Face, e: Event| {
+        let mut designer = designer.borrow_mut();
+        let frame = FRAME.load(Ordering::Relaxed);
+        let seconds = frame / animation_speed;
+
+        if let Some(args) = e.render_args() {
+            let designer = &*designer;
+            let info = match designer.accepted {
+                Some((ref dungeon, ref statistic)) => {
+                    draw_dungeon(gl, args.viewport(), &spritesheet, dungeon, (0.0, 0.0), (tile_width, tile_height), 1.0, seconds);
+                    format!("Accepted dungeon (ranking {})", statistic.fitness)
+                }
+                None => {
+                    let shown = designer.interactive.shown(designer.shown);
+                    for (index, &(_, ref statistic)) in shown.iter().enumerate() {
+                        let x = (index as i32 % columns) * cell_width;
+                        let y = (index as i32 / columns) * cell_height;
+                        if let Some(ref dungeon) = statistic.phenotype {
+                            let corner = (x as f64 + 2.0, y as f64 + 2.0);
+                            draw_dungeon(gl, args.viewport(), &spritesheet, dungeon, corner, (tile_width, tile_height), scale, seconds);
+                        }
+                        if designer.picks.contains(&index) {
+                            Sprite::outline(gl, args.viewport(), x + 1, y + 1, cell_width - 2, cell_height - 2, [0.0, 1.0, 0.0, 1.0]);
+                        }
+                        if index == designer.cursor {
+                            Sprite::outline(gl, args.viewport(), x, y, cell_width, cell_height, [1.0, 1.0, 0.0, 1.0]);
+                        }
+                    }
+                    format!("Generation {}: {} picked, dungeon no. #{} (ranking {})",
+                            designer.interactive.generation(),
+                            designer.picks.len(),
+                            designer.cursor,
+                            shown[designer.cursor].1.fitness)
+                }
+            };
+            render_text(face, gl, args.viewport(), 10.0, 410.0, &info[..]);
+        };
+
+        if let Some(position) = e.mouse_cursor_args() {
+            designer.mouse = position;
+        }
+        if designer.accepted.is_none() {
+            let n = designer.interactive.shown(designer.shown).len();
+            let columns = columns as usize;
+            match e.press_args() {
+                Some(Button::Keyboard(key)) => {
+                    let cursor = designer.cursor;
+                    match key {
+                        Key::Left => designer.cursor = (cursor + n - 1) % n,
+                        Key::Right => designer.cursor = (cursor + 1) % n,
+                        Key::Up if cursor >= columns => designer.cursor = cursor - columns,
+                        Key::Down if cursor + columns < n => designer.cursor = cursor + columns,
+                        Key::Space => designer.toggle(cursor),
+                        Key::N => designer.breed(),
+                        Key::Return => designer.accept(path.as_ref().map(|path| &path[..])),
+                        _ => ()
+                    }
+                }
+                Some(Button::Mouse(MouseButton::Left)) => {
+                    let column = designer.mouse[0] as usize / cell_width as usize;
+                    let row = designer.mouse[1] as usize / cell_height as usize;
+                    let index = row * columns + column;
+                    if column < columns && index < n {
+                        designer.cursor = index;
+                        designer.toggle(index);
+                    }
+                }
+                _ => ()
+            }
+        }
+
+        FRAME.fetch_add(1, Ordering::Relaxed);
+    })
+}
+
+pub fn chapter2_entry(config: &Config, registry: &Registry) -> Result<ChapterCallback, ConfigErrors> {
+
+    if let Some(settings) = try!(load_interactive(config)) {
+        return chapter2_interactive(config, registry, settings);
+    }
+
+    let vars = try!(config.get_table(None, "main"));
+    let (tile_width, tile_height) = try!(tile_size(config));
+    let (tile_width, tile_height) = (tile_width as i32, tile_height as i32);
+    let animation_speed = try!(config.get_default(&vars, "animation_speed", 10));
+
+    let outcome = try!(evolve(config, registry, try!(run_seed(config))));
+    let engine = try!(load_mu_lambda(config)).engine;
+    let pareto = engine == Engine::Nsga2;
+    if pareto {
+        let names: Vec<String> = try!(load_evaluations(config, &registry.evaluations, &try!(load_seed(config)))).into_iter().map(|(name, _, _, _)| name).collect();
+        println!("Pareto front of {} dungeons, scored on {}.", outcome.winners.len(), names.join(" / "));
+    }
+    let descriptors = match engine {
+        Engine::MapElites(x, y) => vec![x.descriptor, y.descriptor],
+        _ => vec![]
+    };
+    if let Some(ref grid) = outcome.grid {
+        println!("{} of {} bins filled ({} across, {} up); use the arrow keys to move between bins.",
+                 outcome.winners.len(), grid.cells.len(), descriptors[0], descriptors[1]);
+    }
+    let statistics = try!(load_statistics(config));
+    if let Some(ref path) = statistics.path {
+        for (island, history) in outcome.histories.iter().enumerate() {
+            let path = island_path(Path::new(path), island, outcome.histories.len());
+            write_history(&path, &statistics.format, &outcome.evaluations[..], &history[..], &outcome.stopped);
+        }
+    }
+    if outcome.stopped != StopReason::Iterations {
+        println!("Stopped after {} iterations: {}.", outcome.iterations(), outcome.stopped);
+    }
+    let Outcome { winners, grid, .. } = outcome;
+    let pathfinder = try!(load_pathfinder(config, &try!(load_seed(config))));
+
+    let spritesheet_path = Path::new(try!(spritesheet_location(config)));
+    let spritesheet = SpriteSheet::new(&spritesheet_path);
+
+    Ok(box move |gl: &mut GlGraphics, face: &mut Face, e: Event| {
+        let choice = CHOICE.load(Ordering::Relaxed);
+        let frame = FRAME.load(Ordering::Relaxed);
+
+        // in an archive, the choice is a bin (row by row), which may be empty.
+        let current = match grid {
+            Some(ref grid) => grid.cells[choice as usize].map(|index| &winners[index]),
+            None => Some(&winners[choice as usize])
+        };
+        let seconds = frame / animation_speed;
+        if let Some(args) = e.render_args() {
+            let overlay = OVERLAY.load(Ordering::Relaxed);
+            let mut path = None;
+            if let Some(&(ref dungeon, _)) = current {
+                draw_dungeon(gl, args.viewport(), &spritesheet, dungeon, (0.0, 0.0), (tile_width, tile_height), 1.0, seconds);
+                if overlay {
+                    path = pathfinder.entrance_to_exit(dungeon);
+                    if let Some(ref path) = path {
+                        draw_path(gl, args.viewport(), &path[..], (tile_width, tile_height));
+                    }
+                }
+            }
+            let info = match (current, grid.as_ref()) {
+                (None, Some(grid)) => {
+                    format!("Bin ({}, {}) is empty", choice as usize % grid.width, choice as usize / grid.width)
+                }
+                (Some(&(_, ref statistic)), Some(grid)) => {
+                    format!("Bin ({}, {}): {} {}, {} {} (ranking {})",
+                            choice as usize % grid.width,
+                            choice as usize / grid.width,
+                            descriptors[0],
+                            statistic.descriptors[0],
+                            descriptors[1],
+                            statistic.descriptors[1],
+                            statistic.fitness)
+                }
+                (Some(&(_, ref statistic)), None) if pareto => {
+                    let scores: Vec<String> = statistic.scores.iter().map(|s| format!("{}", s)).collect();
+                    format!("Dungeon no. #{} (born on iteration {}, scores {})",
+                            choice,
+                            statistic.iteration,
+                            scores.join(" / "))
+                }
+                (Some(&(_, ref statistic)), None) if statistic.novelty > 0.0 => {
+                    format!("Dungeon no. #{} (born on iteration {}, ranking {}, novelty {:.3})",
+                            choice,
+                            statistic.iteration,
+                            statistic.fitness,
+                            statistic.novelty)
+                }
+                (Some(&(_, ref statistic)), None) => {
+                    format!("Dungeon no. #{} (born on iteration {}, ranking {})",
+                            choice,
+                            statistic.iteration,
+                            statistic.fitness)
+                }
+                (None, None) => unreachable!()
+            };
+            let info = match path {
+                Some(ref path) if overlay => format!("{}, path of {} steps", info, path.len() - 1),
+                None if overlay && current.is_some() => format!("{}, no path", info),
+                _ => info
+            };
+            render_text(face, gl, args.viewport(), 10.0, 410.0, &info[..]);
+        };
+
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            if key == Key::P {
+                let overlay = OVERLAY.load(Ordering::Relaxed);
+                OVERLAY.store(!overlay, Ordering::Relaxed);
+            }
+            if let Some(ref grid) = grid {
+                // move around the archive, wrapping at the edges.
+                let (w, h) = (grid.width as isize, grid.height as isize);
+                let (mut x, mut y) = (choice % w, choice / w);
+                match key {
+                    Key::Left => x = (x + w - 1) % w,
+                    Key::Right => x = (x + 1) % w,
+                    Key::Up => y = (y + 1) % h,
+                    Key::Down => y = (y + h - 1) % h,
+                    _ => ()
+                }
+                CHOICE.store(y * w + x, Ordering::Relaxed);
+                FRAME.store(0, Ordering::Relaxed);
+            } else {
+                let n = winners.len() as isize;
+                if key == Key::Left {
+                    CHOICE.fetch_sub(1, Ordering::Relaxed);
+                    let new_choice = CHOICE.load(Ordering::Relaxed);
+                    if new_choice < 0 {
+                        CHOICE.store(n - 1, Ordering::Relaxed);
+                        FRAME.store(0, Ordering::Relaxed);
+                    }
+                }
+                else if key == Key::Right {
+                    CHOICE.fetch_add(1, Ordering::Relaxed);
+                    let new_choice = CHOICE.load(Ordering::Relaxed);
+                    CHOICE.store(new_choice % n, Ordering::Relaxed);
+                    FRAME.store(0, Ordering::Relaxed);
+                }
+            }
+        };
+
+        FRAME.fetch_add(1, Ordering::Relaxed);
+    })
+}
+
+/// chapter 2, along with the genotypes and evaluations its
+/// configuration can name.
+pub struct Chapter2 {
+    registry: Registry
+}
+
+impl Chapter2 {
+    pub fn new() -> Chapter2 {
+        Chapter2::with_registry(Registry::with_defaults())
+    }
+
+    /// use a different set of genotypes and evaluations. start from
+    /// `Registry::with_defaults()` to add to the built-in ones.
+    pub fn with_registry(registry: Registry) -> Chapter2 {
+        Chapter2 {
+            registry: registry
+        }
+    }
+}
+
+impl Chapter for Chapter2 {
+    fn name(&self) -> &'static str { "chapter2" }
+
+    fn description(&self) -> &'static str {
+        "Search-based dungeon generation with a mu + lambda evolutionary strategy."
+    }
+
+    fn check(&self, config: &Config) -> Result<(), ConfigErrors> {
+        chapter2_check(config, &self.registry)
+    }
+
+    fn headless(&self, config: &Config, options: &GenerateOptions) -> Result<(), String> {
+        chapter2_generate(config, &self.registry, options).map_err(|errors| errors.to_string())
+    }
+
+    fn summarize(&self, config: &Config) -> Result<RunSummary, String> {
+        chapter2_summarize(config, &self.registry).map_err(|errors| errors.to_string())
+    }
+
+    fn evaluate(&self, config: &Config, maps: &[String]) -> Result<(), String> {
+        match chapter2_evaluate(config, &self.registry, maps) {
+            Ok(0) => Ok(()),
+            Ok(skipped) => Err(format!("{} of {} maps could not be loaded.", skipped, maps.len())),
+            Err(errors) => Err(errors.to_string())
+        }
+    }
+
+    fn interactive(&self, config: &Config) -> Result<ChapterCallback, String> {
+        chapter2_entry(config, &self.registry).map_err(|errors| errors.to_string())
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashSet};
+
+use chapters::chapter2::cell::{Cell};
+use chapters::chapter2::dungeon::{Dungeon, DungeonCells, SurroundingCells, Surrounding};
+use chapters::chapter2::pathfinding::{Pathfinder};
+use chapters::chapter2::phenotype::{Seed};
+use util::config::{Config, ConfigError, ConfigErrors, Section};
+
+/// evaluations are penalties: 0.0 is perfect, higher is worse. they
+/// may capture their settings (see `room_count`).
+pub type EvaluationFn = Box<Fn(&Dungeon) -> f64 + 'static + Send + Sync>;
+
+/// the overall fitness: the scores, weighted.
+pub fn weighted_sum(scores: &[f64], strategies: &[(EvaluationFn, f64)]) -> f64 {
+    scores.iter().zip(strategies.iter()).fold(0.0, |accum, (score, &(_, w))| accum + w * score)
+}
+
+pub fn check_1x1_rooms(dungeon: &Dungeon) -> f64 {
+    let mut hits = 0;
+    for i in 0..dungeon.width as u32 {
+        for j in 0..dungeon.height as u32 {
+            if dungeon.is_empty(i, j) {
+                let sc = SurroundingCells::new(dungeon, i, j, Surrounding::Cardinal);
+                // check to see if all walls surround us.
+                let surrounded = sc.fold(true, |accum, c| accum && !c.is_empty());
+                if surrounded {
+                    hits += 1;
+                }
+            }
+        }
+    }
+    hits as f64
+}
+
+pub fn has_entrance_exit(dungeon: &Dungeon) -> f64 {
+    let mut has_entrance = false;
+    let mut has_exit = false;
+    for i in 0..dungeon.width as u32 {
+        for j in 0..dungeon.height as u32 {
+            if dungeon.has_attribute(i, j, "entrance") {
+                has_entrance = true;
+            }
+            if dungeon.has_attribute(i, j, "exit") {
+                has_exit = true;
+            }
+        }
+    }
+    match (has_entrance, has_exit) {
+        (false, false) => 2.0,
+        (false, true) => 1.0,
+        (true, false) => 1.0,
+        _ => 0.0,
+    }
+}
+
+pub fn doors_are_useful(dungeon: &Dungeon) -> f64 {
+    let mut hits = 0;
+    for i in 0..dungeon.width as u32 {
+        for j in 0..dungeon.height as u32 {
+            // check to see if doors have exactly two walls or gaps abutting them
+            if dungeon.has_attribute(i, j, "door") {
+                let mut count = 0;
+                for sc in SurroundingCells::new(dungeon, i, j, Surrounding::Cardinal) {
+                    if sc.tile.is_none() || sc.has_attribute("wall") {
+                        count += 1;
+                    }
+                }
+                if count != 2 {
+                    hits += 1;
+                }
+            }
+        }
+    }
+    hits as f64
+}
+
+pub type Coords = HashSet<(u32, u32)>;
+
+// helper function.
+fn is_accessible(cell: &Cell) -> bool {
+    cell.has_attribute("floor") || cell.has_attribute("door")
+}
+
+// recursively traverse the dungeon (depth-first)
+fn search(dungeon: &Dungeon, visited: &Coords, x: u32, y: u32) -> Coords {
+    let mut local = visited.clone();
+    assert!(!local.contains(&(x, y)));
+    local.insert((x, y));
+    for sc in SurroundingCells::new(dungeon, x, y, Surrounding::AllDirections) {
+        let new_x = sc.x;
+        let new_y = sc.y;
+        if is_accessible(&sc) && !local.contains(&(new_x, new_y)) {
+            let results = search(&dungeon, &local, new_x, new_y);
+            let search_results: Coords = local.union(&results).cloned().collect();
+            local = search_results.clone();
+        }
+    }
+    local.clone()
+}
+
+pub fn rooms_are_accessible(dungeon: &Dungeon) -> f64 {
+    let mut hits = 0;
+    let dc = DungeonCells::new(&dungeon);
+    let mut visited: Coords = HashSet::new();
+    let floors: Coords = dc.filter(|ref cell| is_accessible(cell)).map(|cell| (cell.x, cell.y)).collect();
+    while {
+        let remaining: Coords = floors.difference(&visited).cloned().collect();
+        match remaining.len() {
+            0 => false,
+            _ => {
+                let &(x, y) = remaining.iter().next().unwrap();
+                let swept = search(&dungeon, &visited, x, y);
+                visited = visited.union(&swept).cloned().collect();
+                let total: Coords = visited.intersection(&floors).cloned().collect();
+                total.len() != floors.len()
+            }
+        }
+    }
+    {
+        hits += 1;
+    }
+    hits as f64
+}
+
+// count the regions of (cardinally) connected cells matching `f`.
+fn regions(dungeon: &Dungeon, f: &Fn(&Cell) -> bool) -> usize {
+    let (w, h) = (dungeon.width as i32, dungeon.height as i32);
+    let mut seen = vec![vec![false; h as usize]; w as usize];
+    let mut count = 0;
+    for i in 0..w {
+        for j in 0..h {
+            if seen[i as usize][j as usize] || !f(dungeon.get_cell(i as u32, j as u32)) {
+                continue;
+            }
+            count += 1;
+            seen[i as usize][j as usize] = true;
+            let mut pending = vec![(i, j)];
+            while let Some((x, y)) = pending.pop() {
+                for &(dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)].iter() {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if !dungeon.in_bounds(nx, ny) || seen[nx as usize][ny as usize] {
+                        continue;
+                    }
+                    if f(dungeon.get_cell(nx as u32, ny as u32)) {
+                        seen[nx as usize][ny as usize] = true;
+                        pending.push((nx, ny));
+                    }
+                }
+            }
+        }
+    }
+    count
+}
+
+/// distance from `value` to the range [min, max] (no upper bound if
+/// `max` is none).
+fn outside(value: f64, min: f64, max: Option<f64>) -> f64 {
+    if value < min {
+        min - value
+    } else {
+        match max {
+            Some(max) if value > max => value - max,
+            _ => 0.0
+        }
+    }
+}
+
+/// the number of rooms: regions of floor separated by walls or doors.
+pub fn count_rooms(dungeon: &Dungeon) -> usize {
+    regions(dungeon, &|cell: &Cell| cell.has_attribute("floor"))
+}
+
+/// penalise a number of rooms (regions of floor separated by walls or
+/// doors) other than `target`.
+pub fn room_count(config: &Config, vars: &Section, _: &Seed) -> Result<EvaluationFn, ConfigErrors> {
+    let target = try!(config.get_integer(vars, "target"));
+    if target < 0 {
+        return Err(ConfigError::invalid(vars.key("target"), "must not be negative.".to_string()).into());
+    }
+    Ok(box move |dungeon: &Dungeon| {
+        (count_rooms(dungeon) as f64 - target as f64).abs()
+    })
+}
+
+// `min` (default 0) and `max` (default unbounded), for evaluations
+// that penalise a count outside a range.
+fn range(config: &Config, vars: &Section) -> Result<(i64, Option<i64>), ConfigErrors> {
+    let mut errors = ConfigErrors::new();
+    let min = errors.check(config.get_default(vars, "min", 0));
+    let max = if vars.contains("max") {
+        errors.check(config.get_integer(vars, "max")).map(|max| Some(max))
+    } else {
+        Some(None)
+    };
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let (min, max): (i64, Option<i64>) = (min.unwrap(), max.unwrap());
+    if min < 0 {
+        errors.push(ConfigError::invalid(vars.key("min"), "must not be negative.".to_string()));
+    }
+    if let Some(max) = max {
+        if max < min {
+            errors.push(ConfigError::invalid(vars.key("max"), format!("is smaller than min ({}).", min)));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok((min, max))
+}
+
+/// penalise fewer than `min` (default 0) or more than `max` (default
+/// unbounded) doors.
+pub fn door_count(config: &Config, vars: &Section, _: &Seed) -> Result<EvaluationFn, ConfigErrors> {
+    let (min, max) = try!(range(config, vars));
+    Ok(box move |dungeon: &Dungeon| {
+        let doors = DungeonCells::new(dungeon).filter(|cell| cell.has_attribute("door")).count();
+        outside(doors as f64, min as f64, max.map(|max| max as f64))
+    })
+}
+
+/// penalise a dungeon whose exit cannot be reached from its entrance
+/// (or that lacks either) with 1.0. paths are found as `moves` and
+/// `passable` say (see `Pathfinder`).
+pub fn exit_reachable(config: &Config, vars: &Section, seed: &Seed) -> Result<EvaluationFn, ConfigErrors> {
+    let pathfinder = try!(Pathfinder::from_config(config, vars, seed));
+    Ok(box move |dungeon: &Dungeon| {
+        if pathfinder.entrance_to_exit(dungeon).is_some() { 0.0 } else { 1.0 }
+    })
+}
+
+/// penalise a path from the entrance to the exit of fewer than `min`
+/// (default 0) or more than `max` (default unbounded) steps. without
+/// a path, the penalty is the number of cells, more than any path
+/// could earn.
+pub fn path_length(config: &Config, vars: &Section, seed: &Seed) -> Result<EvaluationFn, ConfigErrors> {
+    let (min, max) = try!(range(config, vars));
+    let pathfinder = try!(Pathfinder::from_config(config, vars, seed));
+    Ok(box move |dungeon: &Dungeon| {
+        match pathfinder.entrance_to_exit(dungeon) {
+            Some(path) => outside((path.len() - 1) as f64, min as f64, max.map(|max| max as f64)),
+            None => (dungeon.width * dungeon.height) as f64
+        }
+    })
+}
@@ -0,0 +1,88 @@
+use std::sync::mpsc::{Sender, Receiver};
+use std::sync::{mpsc, Arc};
+use threadpool::{ThreadPool};
+
+use chapters::chapter2::evaluation::{EvaluationFn, weighted_sum};
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic};
+use chapters::chapter2::descriptors::{BehaviourFn};
+
+/// the worker threads of a run. they are started once and live as long
+/// as the run does; individuals are handed to them in chunks, so that
+/// a generation costs a few messages rather than one per dungeon.
+pub struct Evaluator {
+    pool: ThreadPool,
+    threads: usize,
+    chunk_size: usize, // individuals per job (0 for an even share per thread)
+    evaluations: Arc<Vec<(EvaluationFn, f64)>>,
+    behaviour: Arc<Option<BehaviourFn>>,
+}
+
+impl Evaluator {
+    pub fn new(threads: usize, chunk_size: usize, evaluations: Vec<(EvaluationFn, f64)>) -> Evaluator {
+        Evaluator {
+            pool: ThreadPool::new(threads),
+            threads: threads,
+            chunk_size: chunk_size,
+            evaluations: Arc::new(evaluations),
+            behaviour: Arc::new(None),
+        }
+    }
+
+    /// characterise every individual evaluated from now on.
+    pub fn set_behaviour(&mut self, behaviour: BehaviourFn) {
+        self.behaviour = Arc::new(Some(behaviour));
+    }
+
+    pub fn weights(&self) -> Vec<f64> { self.evaluations.iter().map(|&(_, weight)| weight).collect() }
+
+    /// generate and score every individual (each with the mutation
+    /// strength it was made with), keeping its phenotype. the results
+    /// come back in the same order.
+    pub fn evaluate<G: Genotype + Send + 'static>(&self, individuals: Vec<(G, f64)>, iteration: u32) -> Vec<(G, Statistic)> {
+        let n = individuals.len();
+        let threads = self.threads.max(1);
+        let size = match self.chunk_size {
+            0 => ((n + threads - 1) / threads).max(1),
+            size => size
+        };
+        let mut chunks: Vec<Vec<(usize, G, f64)>> = vec![];
+        for (index, (individual, mutation)) in individuals.into_iter().enumerate() {
+            if index % size == 0 {
+                chunks.push(Vec::with_capacity(size));
+            }
+            chunks.last_mut().unwrap().push((index, individual, mutation));
+        }
+        let jobs = chunks.len();
+        let (tx, rx): (Sender<Vec<(usize, G, Statistic)>>, Receiver<Vec<(usize, G, Statistic)>>) = mpsc::channel();
+        for chunk in chunks.into_iter() {
+            let sender = tx.clone();
+            let fns = self.evaluations.clone();
+            let behaviour = self.behaviour.clone();
+            self.pool.execute(move || {
+                let results = chunk.into_iter().map(|(index, individual, mutation)| {
+                    let dungeon = individual.generate();
+                    let scores = individual.evaluate(&dungeon, &fns[..]);
+                    let fitness = weighted_sum(&scores[..], &fns[..]);
+                    let mut statistic = Statistic::new(iteration, fitness, scores);
+                    statistic.mutation = mutation;
+                    statistic.fingerprint = dungeon.fingerprint();
+                    if let Some(ref f) = *behaviour {
+                        statistic.descriptors = f(&dungeon);
+                    }
+                    statistic.phenotype = Some(dungeon);
+                    (index, individual, statistic)
+                }).collect();
+                sender.send(results).unwrap();
+            });
+        }
+        // put everyone back in place, whatever order the chunks finished in.
+        let mut results: Vec<Option<(G, Statistic)>> = (0..n).map(|_| None).collect();
+        for _ in 0..jobs {
+            for (index, individual, statistic) in rx.recv().unwrap().into_iter() {
+                results[index] = Some((individual, statistic));
+            }
+        }
+        results.into_iter().map(|r| r.unwrap()).collect()
+    }
+}
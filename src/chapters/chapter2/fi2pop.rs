@@ -0,0 +1,126 @@
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic, Generation};
+use chapters::chapter2::stopping::{StopReason};
+use chapters::chapter2::mu_lambda::{MuLambda, Child};
+use chapters::chapter2::selection::{Replacement};
+
+/// feasible-infeasible two-population evolution (FI-2Pop).
+/// evaluations marked `hard` are constraints: an individual that
+/// scores 0.0 on all of them is feasible, whatever their weights. the
+/// feasible population is ranked on the weighted sum of the other
+/// evaluations (its quality), the infeasible one on the weighted sum
+/// of the hard ones (how far it is from being feasible). both breed,
+/// and each child joins whichever population it belongs to, so
+/// offspring migrate between the two.
+pub struct Fi2Pop<G: Genotype> {
+    mulambda: MuLambda<G>,
+    hard: Vec<bool>, // one per evaluation
+}
+
+/// the last iteration's populations, best first.
+pub struct Populations<G> {
+    pub feasible: Vec<(G, Statistic)>,
+    pub infeasible: Vec<(G, Statistic)>,
+}
+
+impl<G: Genotype + Clone + Send + 'static> Fi2Pop<G> {
+    pub fn new(mulambda: MuLambda<G>, hard: Vec<bool>) -> Fi2Pop<G> {
+        Fi2Pop {
+            mulambda: mulambda,
+            hard: hard,
+        }
+    }
+
+    /// a summary of every generation (see `MuLambda::history`).
+    pub fn history(&self) -> &[Generation] { self.mulambda.history() }
+
+    /// why the run ended (see `MuLambda::stop_reason`).
+    pub fn stop_reason(&self) -> StopReason { self.mulambda.stop_reason() }
+
+    pub fn run(&mut self) -> Populations<G> {
+        let (mut populations, first) = match self.mulambda.restore() {
+            // splitting again gives the same populations, in the same order.
+            Some((iteration, population)) => (self.split(population, iteration - 1), iteration),
+            None => {
+                let primer = self.mulambda.initialize();
+                let primer = self.mulambda.evaluate(primer, 0);
+                let populations = self.split(primer, 0);
+                self.record(0, &populations);
+                (populations, 1)
+            }
+        };
+        for iteration in first..self.mulambda.iterations() {
+            if self.mulambda.stopped() {
+                break;
+            }
+            let mu = self.mulambda.mu();
+            let feasible: Vec<(G, Statistic)> = populations.feasible.into_iter().take(mu).collect();
+            let infeasible: Vec<(G, Statistic)> = populations.infeasible.into_iter().take(mu).collect();
+            // share the offspring between the populations that have parents.
+            let lambda = self.mulambda.lambda();
+            let from_infeasible = match (feasible.is_empty(), infeasible.is_empty()) {
+                (true, _) => lambda,
+                (_, true) => 0,
+                _ => lambda / 2
+            };
+            let mut offspring = self.breed(&feasible[..], iteration, 0, lambda - from_infeasible);
+            offspring.extend(self.breed(&infeasible[..], iteration, lambda - from_infeasible, from_infeasible));
+            let offspring = self.mulambda.evaluate_offspring(offspring, iteration);
+            let candidates = match *self.mulambda.replacement() {
+                Replacement::Plus => feasible.into_iter().chain(infeasible.into_iter()).chain(offspring.into_iter()).collect(),
+                Replacement::Comma => offspring,
+            };
+            populations = self.split(candidates, iteration);
+            self.record(iteration, &populations);
+        }
+        populations
+    }
+
+    // both populations make up the generation.
+    fn record(&mut self, iteration: u32, populations: &Populations<G>) {
+        let everyone: Vec<&(G, Statistic)> = populations.feasible.iter().chain(populations.infeasible.iter()).collect();
+        let population: Vec<&Statistic> = everyone.iter().map(|&&(_, ref statistic)| statistic).collect();
+        self.mulambda.record(iteration, &population[..]);
+        self.mulambda.save(iteration, &everyone[..]);
+    }
+
+    fn breed(&self, population: &[(G, Statistic)], iteration: u32, first: usize, n: usize) -> Vec<Child<G>> {
+        if n == 0 {
+            return vec![];
+        }
+        let rankings: Vec<f64> = population.iter().map(|&(_, ref statistic)| statistic.ranking).collect();
+        self.mulambda.breed_some(population, &rankings[..], iteration, first, n)
+    }
+
+    // sort candidates into the two populations, each ranked on its own
+    // terms.
+    fn split(&self, candidates: Vec<(G, Statistic)>, iteration: u32) -> Populations<G> {
+        let weights = self.mulambda.weights();
+        let mut feasible = vec![];
+        let mut infeasible = vec![];
+        for (individual, mut statistic) in candidates.into_iter() {
+            // feasibility depends on the raw scores alone: a weight only
+            // says how much a violation counts when ranking.
+            let (mut quality, mut violation, mut violated) = (0.0, 0.0, false);
+            for ((&score, &weight), &hard) in statistic.scores.iter().zip(weights.iter()).zip(self.hard.iter()) {
+                if hard {
+                    violation += score * weight;
+                    violated = violated || score > 0.0;
+                } else {
+                    quality += score * weight;
+                }
+            }
+            if violated {
+                statistic.ranking = violation;
+                infeasible.push((individual, statistic));
+            } else {
+                statistic.ranking = quality;
+                feasible.push((individual, statistic));
+            }
+        }
+        Populations {
+            feasible: self.mulambda.rank(feasible, iteration),
+            infeasible: self.mulambda.rank(infeasible, iteration),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::evaluation::{EvaluationFn};
+use chapters::chapter2::crossover::{Crossover};
+
+use std::any::{Any};
+use rand::{Rng};
+use rustc_serialize::{Encodable, Decodable};
+use rustc_serialize::json;
+
+pub trait Genotype: Send + Clone {
+    /// initialize the genotype.
+    fn initialize<R: Rng>(&self, _: &mut R) -> Self { self.clone() }
+    /// mutate the genotype.
+    fn mutate<R: Rng>(&mut self, _: &mut R, _: f64) { }
+    /// combine with another parent. the default keeps this parent.
+    fn crossover<R: Rng>(&self, _: &mut R, _: &Self, _: &Crossover) -> Self { self.clone() }
+    /// generate a phenotype.
+    fn generate(&self) -> Dungeon;
+    /// serialise, for checkpoints.
+    fn encode_json(&self) -> String;
+    /// deserialise a genotype of the same kind (as `self`).
+    fn decode_json(&self, json: &str) -> Result<Self, String>;
+    /// serialise only what differs between genotypes of a run (not the
+    /// seed or settings they share), for `genome_hash`.
+    fn encode_genome(&self) -> String;
+
+    /// score a phenotype against every evaluation (unweighted).
+    fn evaluate(&self, dungeon: &Dungeon, strategies: &[(EvaluationFn, f64)]) -> Vec<f64> {
+        strategies.iter().map(|&(ref f, _)| f(dungeon)).collect()
+    }
+}
+
+/// `encode_json` for any serialisable genotype.
+pub fn to_json<G: Encodable>(genotype: &G) -> String {
+    json::encode(genotype).unwrap()
+}
+
+/// `decode_json` for any serialisable genotype.
+pub fn from_json<G: Decodable>(json: &str) -> Result<G, String> {
+    json::decode(json).map_err(|why| why.to_string())
+}
+
+/// `Genotype` has generic methods, so it cannot be a trait object.
+/// this object-safe mirror is implemented for every genotype, and
+/// `Box<AnyGenotype>` is itself a `Genotype`, so a genotype chosen at
+/// runtime can be run like any other.
+pub trait AnyGenotype: Send {
+    fn initialize_boxed(&self, rng: &mut Rng) -> Box<AnyGenotype>;
+    fn mutate_boxed(&mut self, rng: &mut Rng, percentage: f64);
+    fn crossover_boxed(&self, rng: &mut Rng, other: &AnyGenotype, scheme: &Crossover) -> Box<AnyGenotype>;
+    fn generate_boxed(&self) -> Dungeon;
+    fn clone_boxed(&self) -> Box<AnyGenotype>;
+    fn encode_boxed(&self) -> String;
+    fn decode_boxed(&self, json: &str) -> Result<Box<AnyGenotype>, String>;
+    fn encode_genome_boxed(&self) -> String;
+    fn as_any(&self) -> &Any;
+}
+
+impl<G: Genotype + 'static> AnyGenotype for G {
+    fn initialize_boxed(&self, mut rng: &mut Rng) -> Box<AnyGenotype> {
+        box self.initialize(&mut rng)
+    }
+
+    fn mutate_boxed(&mut self, mut rng: &mut Rng, percentage: f64) {
+        self.mutate(&mut rng, percentage)
+    }
+
+    fn crossover_boxed(&self, mut rng: &mut Rng, other: &AnyGenotype, scheme: &Crossover) -> Box<AnyGenotype> {
+        // parents of different genotypes cannot be combined.
+        match other.as_any().downcast_ref::<G>() {
+            Some(other) => box self.crossover(&mut rng, other, scheme),
+            None => box self.clone()
+        }
+    }
+
+    fn generate_boxed(&self) -> Dungeon {
+        self.generate()
+    }
+
+    fn clone_boxed(&self) -> Box<AnyGenotype> {
+        box self.clone()
+    }
+
+    fn encode_boxed(&self) -> String {
+        self.encode_json()
+    }
+
+    fn decode_boxed(&self, json: &str) -> Result<Box<AnyGenotype>, String> {
+        self.decode_json(json).map(|g| box g as Box<AnyGenotype>)
+    }
+
+    fn encode_genome_boxed(&self) -> String {
+        self.encode_genome()
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+impl Clone for Box<AnyGenotype> {
+    fn clone(&self) -> Box<AnyGenotype> {
+        (**self).clone_boxed()
+    }
+}
+
+// NB: always go through `**self`, or the call would resolve to the
+// blanket `AnyGenotype` impl of the box itself and recurse.
+impl Genotype for Box<AnyGenotype> {
+    fn initialize<R: Rng>(&self, rng: &mut R) -> Box<AnyGenotype> {
+        (**self).initialize_boxed(rng)
+    }
+
+    fn mutate<R: Rng>(&mut self, rng: &mut R, percentage: f64) {
+        (**self).mutate_boxed(rng, percentage)
+    }
+
+    fn crossover<R: Rng>(&self, rng: &mut R, other: &Box<AnyGenotype>, scheme: &Crossover) -> Box<AnyGenotype> {
+        (**self).crossover_boxed(rng, &**other, scheme)
+    }
+
+    fn generate(&self) -> Dungeon {
+        (**self).generate_boxed()
+    }
+
+    fn encode_json(&self) -> String {
+        (**self).encode_boxed()
+    }
+
+    fn decode_json(&self, json: &str) -> Result<Box<AnyGenotype>, String> {
+        (**self).decode_boxed(json)
+    }
+
+    fn encode_genome(&self) -> String {
+        (**self).encode_genome_boxed()
+    }
+}
@@ -0,0 +1,192 @@
+use std::mem;
+
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic};
+use chapters::chapter2::mu_lambda::{MuLambda};
+use util::config::{Config, ConfigError, ConfigErrors};
+
+/// what the designer's picks do for the next generation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Preference {
+    Parents,    // only the picks breed
+    Bonus(f64), // the picks' ranking improves by this much
+}
+
+/// the `[interactive]` settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InteractiveSettings {
+    pub shown: usize, // dungeons shown per generation (the best ones)
+    pub preference: Preference,
+    pub path: Option<String>, // where to write the accepted dungeon, if anywhere
+}
+
+impl InteractiveSettings {
+    /// `shown`, `preference` ("parents" or "bonus") and `bonus`, and an
+    /// optional `path`; none unless `enabled`.
+    pub fn from_config(config: &Config) -> Result<Option<InteractiveSettings>, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let vars = try!(config.get_optional_table(None, "interactive"));
+        let enabled = errors.check(config.get_default(&vars, "enabled", false));
+        let shown = errors.check(config.get_default(&vars, "shown", 9));
+        let bonus = errors.check(config.get_default(&vars, "bonus", 10.0));
+        let preference = match errors.check(config.get_default(&vars, "preference", "parents".to_string())) {
+            Some(ref name) if name == "parents" => Some(Preference::Parents),
+            Some(ref name) if name == "bonus" => bonus.map(Preference::Bonus),
+            Some(name) => {
+                let why = format!("unknown preference `{}` (expected parents or bonus).", name);
+                errors.push(ConfigError::invalid(vars.key("preference"), why));
+                None
+            }
+            None => None
+        };
+        let path = if vars.contains("path") {
+            errors.check(config.get_string(&vars, "path")).map(|path| Some(path.to_string()))
+        } else {
+            Some(None)
+        };
+        if let Some(0) = shown {
+            errors.push(ConfigError::invalid(vars.key("shown"), "must be at least 1.".to_string()));
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        if !enabled.unwrap() {
+            return Ok(None);
+        }
+        Ok(Some(InteractiveSettings {
+            shown: shown.unwrap(),
+            preference: preference.unwrap(),
+            path: path.unwrap(),
+        }))
+    }
+}
+
+/// interactive evolution: the designer's picks from every generation
+/// steer the next one, for as long as it takes them to accept a
+/// dungeon (the configured iterations and stop criteria do not apply).
+pub struct Interactive<G: Genotype> {
+    mulambda: MuLambda<G>,
+    preference: Preference,
+    candidates: Vec<(G, Statistic)>, // the current generation, best first
+}
+
+impl<G: Genotype + Clone + Send + 'static> Interactive<G> {
+    pub fn new(mut mulambda: MuLambda<G>, preference: Preference) -> Interactive<G> {
+        let candidates = with_phenotypes(mulambda.start());
+        Interactive {
+            mulambda: mulambda,
+            preference: preference,
+            candidates: candidates,
+        }
+    }
+
+    /// how many generations came before this one.
+    pub fn generation(&self) -> u32 { self.mulambda.iteration() - 1 }
+
+    /// the best `n` of the current generation, best first.
+    pub fn shown(&self, n: usize) -> &[(G, Statistic)] {
+        &self.candidates[..n.min(self.candidates.len())]
+    }
+
+    /// breed the next generation, given the designer's picks (indices
+    /// into `shown`). without picks, it is bred as usual.
+    pub fn advance(&mut self, picks: &[usize]) {
+        let candidates = mem::replace(&mut self.candidates, vec![]);
+        let candidates = match self.preference {
+            Preference::Parents if !picks.is_empty() => {
+                candidates.into_iter().enumerate()
+                    .filter(|&(index, _)| picks.contains(&index))
+                    .map(|(_, candidate)| candidate)
+                    .collect()
+            }
+            Preference::Bonus(bonus) => {
+                let mut candidates = candidates;
+                for &index in picks.iter() {
+                    candidates[index].1.ranking -= bonus;
+                }
+                let iteration = self.mulambda.iteration();
+                self.mulambda.rank(candidates, iteration)
+            }
+            _ => candidates
+        };
+        let candidates = self.mulambda.step(candidates);
+        self.candidates = with_phenotypes(self.without_bonus(candidates));
+    }
+
+    // the bonus is only for the selection it was given for: picks that
+    // survive (with `plus` replacement) would otherwise keep it, and
+    // gain another each time they are picked. novelty search ranks
+    // everyone afresh anyway.
+    fn without_bonus(&self, mut candidates: Vec<(G, Statistic)>) -> Vec<(G, Statistic)> {
+        match self.preference {
+            Preference::Bonus(_) if !self.mulambda.ranks_on_novelty() => {
+                for &mut (_, ref mut statistic) in candidates.iter_mut() {
+                    statistic.ranking = statistic.fitness;
+                }
+                let iteration = self.mulambda.iteration();
+                self.mulambda.rank(candidates, iteration)
+            }
+            _ => candidates
+        }
+    }
+}
+
+// candidates that came from the cache have no dungeon to show, so
+// generate theirs again.
+fn with_phenotypes<G: Genotype>(mut candidates: Vec<(G, Statistic)>) -> Vec<(G, Statistic)> {
+    for &mut (ref individual, ref mut statistic) in candidates.iter_mut() {
+        if statistic.phenotype.is_none() {
+            statistic.phenotype = Some(individual.generate());
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use chapters::chapter2::adaptation::{Adaptation};
+    use chapters::chapter2::celloption::{CellOptions};
+    use chapters::chapter2::crossover::{Crossover};
+    use chapters::chapter2::evaluation::{EvaluationFn, check_1x1_rooms};
+    use chapters::chapter2::mu_lambda::{MuLambda};
+    use chapters::chapter2::phenotype::{Seed};
+    use chapters::chapter2::random_seed::{RandomSeed};
+    use chapters::chapter2::selection::{Selection, Replacement};
+    use super::{Interactive, Preference};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn interactive(preference: Preference) -> Interactive<RandomSeed> {
+        let seed = Seed::new(10, 10,
+                             CellOptions::new(&names(&["floor", "wall", "door", "entrance", "exit"])[..]),
+                             CellOptions::new(&names(&["chest"])[..]),
+                             CellOptions::new(&names(&["orc"])[..]),
+                             0.1);
+        let funcs = vec![box check_1x1_rooms as EvaluationFn];
+        let mulambda = MuLambda::new(1, 0, 10, 4, 4, 0.5,
+                                     Adaptation::Fixed, Selection::Truncation, Replacement::Plus,
+                                     0.0, Crossover::OnePoint,
+                                     RandomSeed::new(&seed), funcs, vec![1.0], 3);
+        Interactive::new(mulambda, preference)
+    }
+
+    #[test]
+    fn bonuses_do_not_outlive_their_generation() {
+        let mut interactive = interactive(Preference::Bonus(10.0));
+        for _ in 0..3 {
+            interactive.advance(&[0, 1]);
+            for &(_, ref statistic) in interactive.shown(8).iter() {
+                assert_eq!(statistic.ranking, statistic.fitness);
+            }
+        }
+    }
+
+    #[test]
+    fn shown_dungeons_have_phenotypes() {
+        let mut interactive = interactive(Preference::Parents);
+        interactive.advance(&[0]);
+        assert!(interactive.shown(8).iter().all(|&(_, ref statistic)| statistic.phenotype.is_some()));
+    }
+}
@@ -0,0 +1,188 @@
+use std::mem;
+use std::thread;
+
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic, Generation};
+use chapters::chapter2::stopping::{StopReason};
+use chapters::chapter2::mu_lambda::{MuLambda};
+use util::config::{Config, ConfigError, ConfigErrors};
+
+/// which islands each island sends its migrants to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Topology {
+    Ring, // the next island along (the last one sends to the first)
+    All,  // every other island
+}
+
+/// the `[islands]` settings. each `[islands.<n>]` table is laid over
+/// the whole configuration for island n (counting from 0), so any
+/// setting can differ from one island to the next.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IslandSettings {
+    pub count: usize,
+    pub interval: u32, // generations between migrations
+    pub migrants: usize, // the best individuals each island sends
+    pub topology: Topology,
+}
+
+// the keys of `[islands]` that are not islands.
+static SETTINGS: [&'static str; 4] = ["count", "interval", "migrants", "topology"];
+
+impl IslandSettings {
+    /// `count`, `interval`, `migrants` and `topology`; none when there
+    /// is a single island.
+    pub fn from_config(config: &Config) -> Result<Option<IslandSettings>, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let vars = try!(config.get_optional_table(None, "islands"));
+        let count = errors.check(config.get_default(&vars, "count", 1));
+        let interval = errors.check(config.get_default(&vars, "interval", 10));
+        let migrants = errors.check(config.get_default(&vars, "migrants", 2));
+        let topology = match errors.check(config.get_default(&vars, "topology", "ring".to_string())) {
+            Some(ref name) if name == "ring" => Some(Topology::Ring),
+            Some(ref name) if name == "all" => Some(Topology::All),
+            Some(name) => {
+                let why = format!("unknown topology `{}` (expected ring or all).", name);
+                errors.push(ConfigError::invalid(vars.key("topology"), why));
+                None
+            }
+            None => None
+        };
+        if let Some(0) = count {
+            errors.push(ConfigError::invalid(vars.key("count"), "must be at least 1.".to_string()));
+        }
+        if let Some(0) = interval {
+            errors.push(ConfigError::invalid(vars.key("interval"), "must be at least 1.".to_string()));
+        }
+        for name in config.get_listing(&vars, SETTINGS.to_vec()).into_iter() {
+            match name.parse::<usize>() {
+                Ok(n) if count.map_or(true, |count| n < count) => {
+                    errors.check(config.get_table(Some(&vars), &name[..]));
+                }
+                _ => {
+                    let why = format!("is not an island (expected 0 to {}).", count.unwrap_or(1).max(1) - 1);
+                    errors.push(ConfigError::invalid(vars.key(&name[..]), why));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        if count.unwrap() == 1 {
+            return Ok(None);
+        }
+        Ok(Some(IslandSettings {
+            count: count.unwrap(),
+            interval: interval.unwrap(),
+            migrants: migrants.unwrap(),
+            topology: topology.unwrap(),
+        }))
+    }
+
+    /// the configuration of island `island`.
+    pub fn island_config(config: &Config, island: usize) -> Result<Config, ConfigErrors> {
+        let vars = try!(config.get_optional_table(None, "islands"));
+        let overrides = try!(config.get_optional_table(Some(&vars), &island.to_string()[..]));
+        Ok(config.overlay(&overrides))
+    }
+}
+
+/// the island model: several `MuLambda` populations evolve side by
+/// side (each in its own thread), and every `interval` generations
+/// each one sends copies of its best individuals to its neighbours,
+/// where they replace the worst. migrants are evaluated again on
+/// arrival, as islands may weigh evaluations differently.
+pub struct Islands<G: Genotype> {
+    islands: Vec<MuLambda<G>>,
+    settings: IslandSettings,
+}
+
+impl<G: Genotype + Clone + Send + 'static> Islands<G> {
+    pub fn new(islands: Vec<MuLambda<G>>, settings: IslandSettings) -> Islands<G> {
+        Islands {
+            islands: islands,
+            settings: settings,
+        }
+    }
+
+    /// a summary of every generation of each island.
+    pub fn histories(&self) -> Vec<Vec<Generation>> {
+        self.islands.iter().map(|island| island.history().to_vec()).collect()
+    }
+
+    /// why the last island to finish did.
+    pub fn stop_reason(&self) -> StopReason {
+        let mut last: Option<&MuLambda<G>> = None;
+        for island in self.islands.iter() {
+            if last.map_or(true, |last| island.history().len() > last.history().len()) {
+                last = Some(island);
+            }
+        }
+        last.map(|island| island.stop_reason()).unwrap_or(StopReason::Iterations)
+    }
+
+    /// run every island to the end and return each one's last
+    /// candidates, best first.
+    pub fn run(&mut self) -> Vec<Vec<(G, Statistic)>> {
+        let interval = self.settings.interval;
+        let starting = (0..self.islands.len()).map(|_| None).collect();
+        // the first generation counts towards the first interval.
+        let mut populations = self.advance(starting, interval - 1);
+        while self.islands.iter().any(|island| !island.finished()) {
+            let migrated = self.migrate(populations);
+            populations = self.advance(migrated.into_iter().map(Some).collect(), interval);
+        }
+        populations
+    }
+
+    // run up to `generations` generations on every island, in parallel.
+    // an island without a population starts one first.
+    fn advance(&mut self, populations: Vec<Option<Vec<(G, Statistic)>>>, generations: u32) -> Vec<Vec<(G, Statistic)>> {
+        let islands = mem::replace(&mut self.islands, vec![]);
+        let handles: Vec<_> = islands.into_iter().zip(populations.into_iter()).map(|(mut island, candidates)| {
+            thread::spawn(move || {
+                let mut candidates = match candidates {
+                    Some(candidates) => candidates,
+                    None => island.start()
+                };
+                for _ in 0..generations {
+                    if island.finished() {
+                        break;
+                    }
+                    candidates = island.step(candidates);
+                }
+                (island, candidates)
+            })
+        }).collect();
+        let mut populations = vec![];
+        for handle in handles.into_iter() {
+            let (island, candidates) = handle.join().unwrap();
+            self.islands.push(island);
+            populations.push(candidates);
+        }
+        populations
+    }
+
+    // send copies of the best of every island that is still running to
+    // its neighbours (among those still running).
+    fn migrate(&mut self, populations: Vec<Vec<(G, Statistic)>>) -> Vec<Vec<(G, Statistic)>> {
+        let running: Vec<usize> = (0..self.islands.len()).filter(|&i| !self.islands[i].finished()).collect();
+        let mut arrivals: Vec<Vec<G>> = populations.iter().map(|_| vec![]).collect();
+        for (position, &from) in running.iter().enumerate() {
+            let destinations: Vec<usize> = match self.settings.topology {
+                Topology::Ring => vec![running[(position + 1) % running.len()]],
+                Topology::All => running.iter().cloned().filter(|&to| to != from).collect(),
+            };
+            for &to in destinations.iter().filter(|&&to| to != from) {
+                let best = populations[from].iter().take(self.settings.migrants).map(|&(ref g, _)| g.clone());
+                arrivals[to].extend(best);
+            }
+        }
+        populations.into_iter().zip(arrivals.into_iter()).enumerate().map(|(i, (candidates, migrants))| {
+            if migrants.is_empty() {
+                candidates
+            } else {
+                self.islands[i].immigrate(candidates, migrants)
+            }
+        }).collect()
+    }
+}
@@ -1,13 +1,14 @@
-use chapter2::dungeon::{Dungeon};
-use chapter2::celloption::{Occupant};
-use chapter2::genotype::{Genotype};
-use chapter2::phenotype::{Seed};
-use util::config::{Config};
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::celloption::{Occupant};
+use chapters::chapter2::genotype::{Genotype, to_json, from_json};
+use chapters::chapter2::crossover::{Crossover};
+use chapters::chapter2::phenotype::{Seed};
+use util::config::{Config, ConfigError, ConfigErrors};
 use util::util::{odds};
 
 use rand::{Rng};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct ListOfWalls {
     seed: Seed,
     walls: Vec<Wall>,
@@ -18,7 +19,7 @@ pub struct ListOfWalls {
     occupants: Vec<(Occupant, (u32, u32))>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 struct Wall {
     x: u32,
     y: u32,
@@ -58,11 +59,22 @@ impl Wall {
 }
 
 impl ListOfWalls {
-    pub fn new(config: &Config, seed: &Seed) -> ListOfWalls {
-        let wall_vars = config.get_table(None, "list-of-walls");
-        let door_chance = config.get_float(wall_vars, "door_chance");
-        let coverage = config.get_float(wall_vars, "coverage");
-        ListOfWalls {
+    pub fn new(config: &Config, seed: &Seed) -> Result<ListOfWalls, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let wall_vars = try!(config.get_table(None, "list-of-walls"));
+        let door_chance = errors.check(config.get_float(&wall_vars, "door_chance"));
+        let coverage = errors.check(config.get_float(&wall_vars, "coverage"));
+        if let Some(coverage) = coverage {
+            if coverage < 0.0 || coverage >= 1.0 {
+                let why = format!("must be at least 0.0 and less than 1.0, not {}.", coverage);
+                errors.push(ConfigError::invalid(wall_vars.key("coverage"), why));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let (door_chance, coverage) = (door_chance.unwrap(), coverage.unwrap());
+        Ok(ListOfWalls {
             seed: seed.clone(),
             door_chance: door_chance,
             coverage: coverage,
@@ -70,7 +82,7 @@ impl ListOfWalls {
             entrance: (0, 0),
             exit: (0, 0),
             occupants: vec![],
-        }
+        })
     }
 }
 
@@ -113,6 +125,24 @@ impl Genotype for ListOfWalls {
         self.occupants = self.seed.random_occupants(rng);
     }
 
+    fn crossover<T: Rng>(&self, rng: &mut T, other: &ListOfWalls, scheme: &Crossover) -> ListOfWalls {
+        let mut child = self.clone();
+        child.walls = scheme.recombine(rng, &self.walls[..], &other.walls[..]);
+        child
+    }
+
+    fn encode_json(&self) -> String {
+        to_json(self)
+    }
+
+    fn decode_json(&self, json: &str) -> Result<ListOfWalls, String> {
+        from_json(json)
+    }
+
+    fn encode_genome(&self) -> String {
+        to_json(&(&self.walls, self.entrance, self.exit, &self.occupants))
+    }
+
     fn generate(&self) -> Dungeon {
         let w = self.seed.width;
         let h = self.seed.height;
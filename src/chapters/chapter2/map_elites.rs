@@ -0,0 +1,212 @@
+use rand::{Rng};
+
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic, Generation};
+use chapters::chapter2::stopping::{StopReason};
+use chapters::chapter2::mu_lambda::{MuLambda, Child};
+use chapters::chapter2::descriptors::{descriptor, combine};
+use util::config::{Config, ConfigError, ConfigErrors, Section};
+use util::util::{derive_rng};
+
+// streams 0 to 4 belong to `MuLambda`.
+const CHOOSE: u64 = 5;
+
+/// one axis of the archive: a behaviour descriptor, split into `bins`
+/// equal bins between `min` and `max` (values outside go to the
+/// first or last bin).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dimension {
+    pub descriptor: String,
+    min: f64,
+    max: f64,
+    pub bins: usize,
+}
+
+impl Dimension {
+    pub fn from_config(config: &Config, vars: &Section) -> Result<Dimension, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let name = errors.check(config.get_string(vars, "descriptor"));
+        let min = errors.check(config.get_float(vars, "min"));
+        let max = errors.check(config.get_float(vars, "max"));
+        let bins = errors.check(config.get_default(vars, "bins", 10));
+        if let Some(name) = name {
+            if let Err(why) = descriptor(name) {
+                errors.push(ConfigError::invalid(vars.key("descriptor"), why));
+            }
+        }
+        if let (Some(min), Some(max)) = (min, max) {
+            if max <= min {
+                errors.push(ConfigError::invalid(vars.key("max"), format!("must be more than min ({}).", min)));
+            }
+        }
+        if let Some(0) = bins {
+            errors.push(ConfigError::invalid(vars.key("bins"), "must be at least 1.".to_string()));
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Dimension {
+            descriptor: name.unwrap().to_string(),
+            min: min.unwrap(),
+            max: max.unwrap(),
+            bins: bins.unwrap(),
+        })
+    }
+
+    pub fn bin(&self, value: f64) -> usize {
+        let scaled = (value - self.min) / (self.max - self.min) * self.bins as f64;
+        if scaled < 0.0 {
+            0
+        } else if scaled as usize >= self.bins {
+            self.bins - 1
+        } else {
+            scaled as usize
+        }
+    }
+}
+
+/// the best individual found for every combination of bins (row by
+/// row), if any.
+pub struct Archive<G> {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Option<(G, Statistic)>>,
+}
+
+/// MAP-Elites: rather than one population, keep an archive of the
+/// best individual per bin of two behaviour descriptors. offspring of
+/// random elites compete only for their own bin, so the archive fills
+/// up with different kinds of dungeons. variation (crossover and
+/// mutation) is that of the wrapped `MuLambda`.
+pub struct MapElites<G: Genotype> {
+    mulambda: MuLambda<G>,
+    x: Dimension,
+    y: Dimension,
+}
+
+impl<G: Genotype + Clone + Send + 'static> MapElites<G> {
+    pub fn new(mut mulambda: MuLambda<G>, x: Dimension, y: Dimension) -> MapElites<G> {
+        // both names were checked by `Dimension::from_config`.
+        let descriptors = vec![descriptor(&x.descriptor[..]).unwrap(), descriptor(&y.descriptor[..]).unwrap()];
+        mulambda.set_behaviour(combine(descriptors));
+        MapElites {
+            mulambda: mulambda,
+            x: x,
+            y: y,
+        }
+    }
+
+    /// a summary of every generation (see `MuLambda::history`).
+    pub fn history(&self) -> &[Generation] { self.mulambda.history() }
+
+    /// why the run ended (see `MuLambda::stop_reason`).
+    pub fn stop_reason(&self) -> StopReason { self.mulambda.stop_reason() }
+
+    /// fill the archive: mu + lambda random individuals first, then
+    /// lambda offspring per iteration.
+    pub fn run(&mut self) -> Archive<G> {
+        let mut archive = Archive {
+            width: self.x.bins,
+            height: self.y.bins,
+            cells: (0..self.x.bins * self.y.bins).map(|_| None).collect(),
+        };
+        let first = match self.mulambda.restore() {
+            Some((iteration, elites)) => {
+                for elite in elites.into_iter() {
+                    self.place(&mut archive, elite);
+                }
+                iteration
+            }
+            None => {
+                let primer = self.mulambda.initialize();
+                for candidate in self.mulambda.evaluate(primer, 0) {
+                    self.place(&mut archive, candidate);
+                }
+                self.record(0, &archive);
+                1
+            }
+        };
+        for iteration in first..self.mulambda.iterations() {
+            if self.mulambda.stopped() {
+                break;
+            }
+            let offspring: Vec<Child<G>> = {
+                let elites: Vec<&(G, Statistic)> = archive.cells.iter().filter_map(|cell| cell.as_ref()).collect();
+                let mut rng = derive_rng(self.mulambda.rng_seed(), &[CHOOSE, iteration as u64]);
+                (0..self.mulambda.lambda()).map(|index| {
+                    let first = *rng.choose(&elites[..]).unwrap();
+                    let second = *rng.choose(&elites[..]).unwrap();
+                    self.mulambda.vary(first, &second.0, iteration, index)
+                }).collect()
+            };
+            for candidate in self.mulambda.evaluate_offspring(offspring, iteration) {
+                self.place(&mut archive, candidate);
+            }
+            self.record(iteration, &archive);
+        }
+        archive
+    }
+
+    // the elites make up the generation.
+    fn record(&mut self, iteration: u32, archive: &Archive<G>) {
+        let elites: Vec<&(G, Statistic)> = archive.cells.iter().filter_map(|cell| cell.as_ref()).collect();
+        let population: Vec<&Statistic> = elites.iter().map(|&&(_, ref s)| s).collect();
+        self.mulambda.record(iteration, &population[..]);
+        self.mulambda.save(iteration, &elites[..]);
+    }
+
+    // keep a candidate if its bin is empty or it beats the elite there.
+    fn place(&self, archive: &mut Archive<G>, candidate: (G, Statistic)) {
+        let index = {
+            let ref descriptors = (candidate.1).descriptors;
+            self.y.bin(descriptors[1]) * archive.width + self.x.bin(descriptors[0])
+        };
+        let better = match archive.cells[index] {
+            Some((_, ref elite)) => (candidate.1).fitness < elite.fitness,
+            None => true
+        };
+        if better {
+            archive.cells[index] = Some(candidate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dimension};
+
+    fn dimension(min: f64, max: f64, bins: usize) -> Dimension {
+        Dimension {
+            descriptor: "room_count".to_string(),
+            min: min,
+            max: max,
+            bins: bins,
+        }
+    }
+
+    #[test]
+    fn values_fall_into_even_bins() {
+        let dimension = dimension(0.0, 10.0, 5);
+        assert_eq!(dimension.bin(0.0), 0);
+        assert_eq!(dimension.bin(1.9), 0);
+        assert_eq!(dimension.bin(2.0), 1);
+        assert_eq!(dimension.bin(5.0), 2);
+        assert_eq!(dimension.bin(9.9), 4);
+    }
+
+    #[test]
+    fn values_outside_are_clamped() {
+        let dimension = dimension(-1.0, 1.0, 4);
+        assert_eq!(dimension.bin(-5.0), 0);
+        assert_eq!(dimension.bin(1.0), 3);
+        assert_eq!(dimension.bin(100.0), 3);
+    }
+
+    #[test]
+    fn a_single_bin_takes_everything() {
+        let dimension = dimension(0.0, 1.0, 1);
+        assert_eq!(dimension.bin(-1.0), 0);
+        assert_eq!(dimension.bin(0.5), 0);
+        assert_eq!(dimension.bin(2.0), 0);
+    }
+}
@@ -0,0 +1,32 @@
+pub mod entry;
+pub mod dungeon;
+pub mod cell;
+pub mod celloption;
+pub mod genotype;
+pub mod statistics;
+pub mod mu_lambda;
+pub mod evaluation;
+pub mod random_seed;
+pub mod list_of_walls;
+pub mod wall_patterns;
+pub mod desirable_properties;
+pub mod phenotype;
+pub mod output;
+pub mod ascii_map;
+pub mod tiled;
+pub mod registry;
+pub mod selection;
+pub mod crossover;
+pub mod nsga2;
+pub mod descriptors;
+pub mod map_elites;
+pub mod novelty;
+pub mod fi2pop;
+pub mod adaptation;
+pub mod stopping;
+pub mod checkpoint;
+pub mod cache;
+pub mod islands;
+pub mod evaluator;
+pub mod interactive;
+pub mod pathfinding;
@@ -0,0 +1,577 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap};
+use std::io::{Write, stdout};
+use std::path::{PathBuf};
+use time::{precise_time_s};
+use rand::{Rng};
+
+use chapters::chapter2::evaluation::{EvaluationFn};
+use chapters::chapter2::evaluator::{Evaluator};
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic, Generation};
+use chapters::chapter2::selection::{Selection, Replacement};
+use chapters::chapter2::crossover::{Crossover};
+use chapters::chapter2::descriptors::{BehaviourFn};
+use chapters::chapter2::novelty::{Novelty};
+use chapters::chapter2::adaptation::{Adaptation};
+use chapters::chapter2::stopping::{StopCriteria, StopReason};
+use chapters::chapter2::checkpoint::{Checkpoint};
+use chapters::chapter2::cache::{Cache, genome_hash};
+use util::util::{shuffle, derive_rng};
+
+// random streams are derived from the run seed, so each use of
+// randomness gets its own stream.
+const INITIALIZE: u64 = 0;
+const SHUFFLE: u64 = 1;
+const MUTATE: u64 = 2;
+const SELECT: u64 = 3;
+const MATE: u64 = 4;
+
+/// an individual yet to be evaluated.
+pub struct Child<G> {
+    pub genotype: G,
+    pub mutation: f64,      // the strength it was mutated with (and passes on)
+    pub parent: Option<f64>, // the fitness of its first parent, if it has one
+}
+
+pub struct MuLambda<G: Genotype> {
+    iterations: u32,
+    current_iteration: u32,
+    mu: usize,     // number to keep
+    lambda: usize, // number to generate
+    mutation: f64, // mutation of genotype to mutate (between 0.0 and 1.0)
+    adaptation: Adaptation,
+    strengths: Vec<f64>, // the (mean) mutation strength of every iteration
+    history: Vec<Generation>,
+    started: f64, // when the run started, in seconds
+    progress: bool, // print a line per generation
+    stopping: StopCriteria,
+    stopped: Option<StopReason>, // why the run ended early, if it did
+    evaluations_done: Cell<usize>, // dungeons evaluated so far
+    evaluation_seconds: Cell<f64>, // time spent evaluating them
+    recorded: (usize, f64), // evaluations done and time spent, as of the last generation recorded
+    checkpoint: Option<(PathBuf, u32)>, // where to save, and every how many iterations
+    run_kind: (String, String), // the engine and strategy, which checkpoints must match
+    resumed: Option<(u32, Vec<(G, Statistic)>)>, // the iteration and population to carry on from
+    cache: RefCell<Option<Cache>>,
+    selection: Selection,
+    replacement: Replacement,
+    crossover_rate: f64, // chance that a child has two parents
+    crossover: Crossover,
+    genotype: G,
+    evaluator: Evaluator,
+    novelty: Option<Novelty>,
+    rng_seed: u64, // the same seed always produces the same run
+}
+
+impl<G: Genotype + Clone + Send + 'static> MuLambda<G> {
+    pub fn new(threads: usize,
+               chunk_size: usize,
+               iterations: u32,
+               mu: usize,
+               lambda: usize,
+               mutation: f64,
+               adaptation: Adaptation,
+               selection: Selection,
+               replacement: Replacement,
+               crossover_rate: f64,
+               crossover: Crossover,
+               genotype: G,
+               funcs: Vec<EvaluationFn>,
+               weights: Vec<f64>,
+               rng_seed: u64) -> MuLambda<G> {
+        MuLambda {
+            iterations: iterations,
+            current_iteration: 0,
+            mu: mu,
+            lambda: lambda,
+            mutation: mutation,
+            adaptation: adaptation,
+            strengths: vec![mutation],
+            history: vec![],
+            started: precise_time_s(),
+            progress: false,
+            stopping: StopCriteria::none(),
+            stopped: None,
+            evaluations_done: Cell::new(0),
+            evaluation_seconds: Cell::new(0.0),
+            recorded: (0, 0.0),
+            checkpoint: None,
+            run_kind: (String::new(), String::new()),
+            resumed: None,
+            cache: RefCell::new(None),
+            selection: selection,
+            replacement: replacement,
+            crossover_rate: crossover_rate,
+            crossover: crossover,
+            genotype: genotype,
+            evaluator: Evaluator::new(threads, chunk_size, funcs.into_iter().zip(weights.iter().cloned()).collect()),
+            novelty: None,
+            rng_seed: rng_seed,
+        }
+    }
+
+    /// run every iteration and return the last one's candidates (the
+    /// population plus its offspring, or only the offspring for
+    /// `(mu, lambda)`), best first.
+    pub fn run(&mut self) -> Vec<(G, Statistic)> {
+        let mut candidates = self.start();
+        while !self.finished() {
+            candidates = self.step(candidates);
+        }
+        candidates
+    }
+
+    /// the first iteration's candidates, best first (or, when resuming,
+    /// those the checkpoint left off with).
+    pub fn start(&mut self) -> Vec<(G, Statistic)> {
+        match self.restore() {
+            Some((iteration, population)) => {
+                self.current_iteration = iteration;
+                population
+            }
+            None => {
+                let primer = self.initialize();
+                let mut candidates = self.evaluate(primer, self.current_iteration);
+                self.score_novelty(&mut candidates[..]);
+                let candidates = self.rank(candidates, self.current_iteration);
+                self.end_iteration(&candidates[..]);
+                candidates
+            }
+        }
+    }
+
+    /// run one iteration on the previous one's candidates.
+    pub fn step(&mut self, candidates: Vec<(G, Statistic)>) -> Vec<(G, Statistic)> {
+        let population: Vec<(G, Statistic)> = candidates.into_iter().take(self.mu).collect();
+        let fitnesses: Vec<f64> = population.iter().map(|&(_, ref statistic)| statistic.ranking).collect();
+        let offspring = self.breed(&population[..], &fitnesses[..], self.current_iteration);
+        let iteration = self.current_iteration;
+        let offspring = self.evaluate_offspring(offspring, iteration);
+        let mut candidates = match self.replacement {
+            Replacement::Plus => population.into_iter().chain(offspring.into_iter()).collect(),
+            Replacement::Comma => offspring,
+        };
+        self.score_novelty(&mut candidates[..]);
+        let candidates = self.rank(candidates, self.current_iteration);
+        self.end_iteration(&candidates[..]);
+        candidates
+    }
+
+    /// whether every iteration ran, or a stop criterion was met.
+    pub fn finished(&self) -> bool {
+        self.current_iteration >= self.iterations || self.stopped.is_some()
+    }
+
+    /// take in individuals from elsewhere, between iterations: they are
+    /// evaluated here (on this run's terms), replace the worst of
+    /// `candidates`, and everyone is ranked again.
+    pub fn immigrate(&mut self, candidates: Vec<(G, Statistic)>, migrants: Vec<G>) -> Vec<(G, Statistic)> {
+        // date them to the last iteration, so novelty search does not
+        // archive them as newborn.
+        let iteration = self.current_iteration.saturating_sub(1);
+        let children = migrants.into_iter().map(|genotype| {
+            Child {
+                genotype: genotype,
+                mutation: self.mutation,
+                parent: None,
+            }
+        }).collect();
+        let arrivals = self.evaluate(children, iteration);
+        let keep = candidates.len().saturating_sub(arrivals.len());
+        let mut candidates: Vec<(G, Statistic)> = candidates.into_iter().take(keep).chain(arrivals.into_iter()).collect();
+        self.score_novelty(&mut candidates[..]);
+        self.rank(candidates, self.current_iteration)
+    }
+
+    /// whether rankings come from novelty search (and are worked out
+    /// afresh every iteration) rather than being the fitness.
+    pub fn ranks_on_novelty(&self) -> bool { self.novelty.is_some() }
+
+    /// characterise every individual evaluated from now on (see
+    /// `Statistic::descriptors`).
+    pub fn set_behaviour(&mut self, behaviour: BehaviourFn) {
+        self.evaluator.set_behaviour(behaviour);
+    }
+
+    /// rank on novelty (possibly blended with fitness) rather than
+    /// fitness alone.
+    pub fn set_novelty(&mut self, novelty: Novelty) {
+        self.set_behaviour(novelty.behaviour());
+        self.novelty = Some(novelty);
+    }
+
+    pub fn mu(&self) -> usize { self.mu }
+
+    pub fn lambda(&self) -> usize { self.lambda }
+
+    pub fn rng_seed(&self) -> u64 { self.rng_seed }
+
+    pub fn iterations(&self) -> u32 { self.iterations }
+
+    /// the next iteration to run.
+    pub fn iteration(&self) -> u32 { self.current_iteration }
+
+    pub fn replacement(&self) -> &Replacement { &self.replacement }
+
+    pub fn weights(&self) -> Vec<f64> { self.evaluator.weights() }
+
+    /// a summary of every generation so far.
+    pub fn history(&self) -> &[Generation] { &self.history[..] }
+
+    /// print a progress line for every generation.
+    pub fn set_progress(&mut self, progress: bool) {
+        self.progress = progress;
+    }
+
+    /// remember the statistics of up to `capacity` genotypes, so that
+    /// duplicates are not evaluated again.
+    pub fn set_cache(&mut self, capacity: usize) {
+        self.cache = RefCell::new(Some(Cache::new(capacity)));
+    }
+
+    /// save a checkpoint to `path` every `every` iterations.
+    pub fn set_checkpoint(&mut self, path: PathBuf, every: u32) {
+        self.checkpoint = Some((path, every));
+    }
+
+    /// name the engine and strategy driving this run, as recorded in
+    /// (and checked against) checkpoints.
+    pub fn set_run_kind(&mut self, engine: &str, strategy: &str) {
+        self.run_kind = (engine.to_string(), strategy.to_string());
+    }
+
+    /// carry on from a checkpoint instead of starting afresh. the run
+    /// seed is the checkpoint's, so that the run goes on as it would
+    /// have. fails if the checkpoint was saved by a run with another
+    /// engine, strategy, mu or lambda, or if its genotypes are not of
+    /// this run's kind.
+    pub fn resume(&mut self, checkpoint: Checkpoint) -> Result<(), String> {
+        let (ref engine, ref strategy) = self.run_kind;
+        if checkpoint.engine != *engine || checkpoint.strategy != *strategy || checkpoint.mu != self.mu || checkpoint.lambda != self.lambda {
+            return Err(format!("it was saved by {} with {} (mu {}, lambda {}), but this run is {} with {} (mu {}, lambda {}).",
+                               checkpoint.engine, checkpoint.strategy, checkpoint.mu, checkpoint.lambda,
+                               engine, strategy, self.mu, self.lambda));
+        }
+        let mut population = vec![];
+        for (encoded, statistic) in checkpoint.population.into_iter() {
+            population.push((try!(self.genotype.decode_json(&encoded[..])), statistic));
+        }
+        self.rng_seed = checkpoint.rng_seed;
+        self.mutation = checkpoint.mutation;
+        self.strengths = checkpoint.strengths;
+        self.evaluations_done.set(checkpoint.evaluations);
+        self.recorded = (checkpoint.evaluations, 0.0);
+        // carry on the clock from where it stopped.
+        let elapsed = checkpoint.history.last().map(|g| g.seconds).unwrap_or(0.0);
+        self.started = precise_time_s() - elapsed;
+        self.history = checkpoint.history;
+        if let Some(ref mut novelty) = self.novelty {
+            novelty.restore(checkpoint.novelty_archive);
+        }
+        self.resumed = Some((checkpoint.iteration, population));
+        Ok(())
+    }
+
+    /// the iteration and population to carry on from, if resuming
+    /// (only the first time this is asked).
+    pub fn restore(&mut self) -> Option<(u32, Vec<(G, Statistic)>)> {
+        self.resumed.take()
+    }
+
+    /// save a checkpoint after `iteration` if one is due. `population`
+    /// is whatever the engine needs to carry on.
+    pub fn save(&self, iteration: u32, population: &[&(G, Statistic)]) {
+        let path = match self.checkpoint {
+            Some((ref path, every)) if (iteration + 1) % every == 0 => path,
+            _ => return
+        };
+        let checkpoint = Checkpoint {
+            engine: self.run_kind.0.clone(),
+            strategy: self.run_kind.1.clone(),
+            mu: self.mu,
+            lambda: self.lambda,
+            iteration: iteration + 1,
+            rng_seed: self.rng_seed,
+            mutation: self.mutation,
+            strengths: self.strengths.clone(),
+            evaluations: self.evaluations_done.get(),
+            history: self.history.clone(),
+            population: population.iter().map(|&&(ref g, ref s)| (g.encode_json(), s.clone())).collect(),
+            novelty_archive: self.novelty.as_ref().map(|n| n.archive().to_vec()).unwrap_or(vec![]),
+        };
+        checkpoint.save(path);
+    }
+
+    /// end the run early when any of these criteria is met.
+    pub fn set_stopping(&mut self, stopping: StopCriteria) {
+        self.stopping = stopping;
+    }
+
+    /// whether a stop criterion was met; engines should not start
+    /// another iteration once it was.
+    pub fn stopped(&self) -> bool { self.stopped.is_some() }
+
+    /// why the run ended (so far).
+    pub fn stop_reason(&self) -> StopReason {
+        self.stopped.clone().unwrap_or(StopReason::Iterations)
+    }
+
+    /// summarise a generation (whatever the engine considers its
+    /// population) into the history, and check the stop criteria.
+    pub fn record(&mut self, iteration: u32, population: &[&Statistic]) {
+        let mutation = self.strengths[(iteration as usize).min(self.strengths.len() - 1)];
+        // the throughput of this generation's evaluations alone.
+        let (evaluations, seconds) = (self.evaluations_done.get(), self.evaluation_seconds.get());
+        let throughput = if seconds > self.recorded.1 {
+            (evaluations - self.recorded.0) as f64 / (seconds - self.recorded.1)
+        } else {
+            0.0
+        };
+        self.recorded = (evaluations, seconds);
+        let generation = Generation::new(iteration, population, mutation, precise_time_s() - self.started, throughput);
+        self.history.push(generation);
+        self.stopped = self.stopping.check(&self.history[..], self.evaluations_done.get());
+        if self.progress {
+            // overwrite the same line, and move on after the last one.
+            let last = self.stopped.is_some() || iteration + 1 >= self.iterations;
+            print!("\r{}{}", self.history[self.history.len() - 1].progress(self.iterations), if last { "\n" } else { "" });
+            stdout().flush().ok();
+        }
+    }
+
+    /// the first mu + lambda individuals, at random.
+    pub fn initialize(&self) -> Vec<Child<G>> {
+        (0..self.mu + self.lambda).map(|i| {
+            let mut rng = derive_rng(self.rng_seed, &[INITIALIZE, i as u64]);
+            Child {
+                genotype: self.genotype.initialize(&mut rng),
+                mutation: self.mutation,
+                parent: None,
+            }
+        }).collect()
+    }
+
+    /// pick lambda parents from a population sorted best first (by
+    /// `fitnesses`, lower is better); each child is a copy of its
+    /// parent (or, at the crossover rate, a combination with a second
+    /// parent), mutated.
+    pub fn breed(&self, population: &[(G, Statistic)], fitnesses: &[f64], iteration: u32) -> Vec<Child<G>> {
+        self.breed_some(population, fitnesses, iteration, 0, self.lambda)
+    }
+
+    /// as `breed`, but only `n` children, numbered from `first`. an
+    /// iteration's children must not share numbers, as each number
+    /// has its own random stream.
+    pub fn breed_some(&self, population: &[(G, Statistic)], fitnesses: &[f64], iteration: u32, first: usize, n: usize) -> Vec<Child<G>> {
+        let mut rng = derive_rng(self.rng_seed, &[SELECT, iteration as u64, first as u64]);
+        let parents = self.selection.select(&mut rng, &fitnesses[..], self.mu, n);
+        let mut rng = derive_rng(self.rng_seed, &[MATE, iteration as u64, first as u64]);
+        let mut mates = self.selection.select(&mut rng, &fitnesses[..], self.mu, n);
+        // truncation picks the same elites in the same order every
+        // time, so shuffle, or every parent would mate with itself.
+        shuffle(&mut rng, &mut mates[..]);
+        parents.into_iter().zip(mates.into_iter()).enumerate().map(|(index, (parent, mate))| {
+            self.vary(&population[parent], &population[mate].0, iteration, first + index)
+        }).collect()
+    }
+
+    /// the `index`th child of an iteration: a copy of `first` (or, at
+    /// the crossover rate, a combination with `second`), mutated.
+    pub fn vary(&self, first: &(G, Statistic), second: &G, iteration: u32, index: usize) -> Child<G> {
+        let (ref parent, ref statistic) = *first;
+        let mut rng = derive_rng(self.rng_seed, &[MUTATE, iteration as u64, index as u64]);
+        let mut baby = if rng.gen::<f64>() < self.crossover_rate {
+            parent.crossover(&mut rng, second, &self.crossover)
+        } else {
+            parent.clone()
+        };
+        let mutation = self.adaptation.inherit(&mut rng, statistic.mutation, self.mutation);
+        baby.mutate(&mut rng, mutation);
+        Child {
+            genotype: baby,
+            mutation: mutation,
+            parent: Some(statistic.fitness),
+        }
+    }
+
+    /// as `evaluate`, for the children of an iteration; the mutation
+    /// strength is then adapted to how well they did.
+    pub fn evaluate_offspring(&mut self, children: Vec<Child<G>>, iteration: u32) -> Vec<(G, Statistic)> {
+        let parents: Vec<Option<f64>> = children.iter().map(|child| child.parent).collect();
+        let strength = children.iter().fold(0.0, |accum, child| accum + child.mutation) / children.len().max(1) as f64;
+        let offspring = self.evaluate(children, iteration);
+        let (better, total) = offspring.iter().zip(parents.iter()).fold((0, 0), |(better, total), (&(_, ref statistic), parent)| {
+            match *parent {
+                Some(fitness) if statistic.fitness < fitness => (better + 1, total + 1),
+                Some(_) => (better, total + 1),
+                None => (better, total)
+            }
+        });
+        self.strengths.push(strength);
+        if total > 0 {
+            self.mutation = self.adaptation.adapt(self.mutation, better as f64 / total as f64);
+        }
+        offspring
+    }
+
+    /// calculate the fitness of each individual (on the worker
+    /// threads), and keep its phenotype. with a cache, a genotype that
+    /// was seen before (or earlier in `children`) is not evaluated
+    /// again; if it was seen in an earlier call, it has no phenotype.
+    pub fn evaluate(&self, children: Vec<Child<G>>, iteration: u32) -> Vec<(G, Statistic)> {
+        let n = children.len();
+        let mut cache = self.cache.borrow_mut();
+        let mut results: Vec<Option<(G, Statistic)>> = (0..n).map(|_| None).collect();
+        let mut first_of: HashMap<u64, usize> = HashMap::new();
+        let mut duplicates = vec![]; // (index, the index it duplicates, genotype, mutation)
+        let mut pending = vec![]; // (index, cache key), in the order sent
+        let mut individuals = vec![];
+        for (index, child) in children.into_iter().enumerate() {
+            let mut key = None;
+            if let Some(ref mut cache) = *cache {
+                let hash = genome_hash(&child.genotype);
+                if let Some(mut statistic) = cache.get(hash) {
+                    statistic.iteration = iteration;
+                    statistic.mutation = child.mutation;
+                    results[index] = Some((child.genotype, statistic));
+                    continue;
+                }
+                if let Some(&original) = first_of.get(&hash) {
+                    duplicates.push((index, original, child.genotype, child.mutation));
+                    continue;
+                }
+                first_of.insert(hash, index);
+                key = Some(hash);
+            }
+            pending.push((index, key));
+            individuals.push((child.genotype, child.mutation));
+        }
+        let started = precise_time_s();
+        let evaluated = self.evaluator.evaluate(individuals, iteration);
+        self.evaluation_seconds.set(self.evaluation_seconds.get() + precise_time_s() - started);
+        self.evaluations_done.set(self.evaluations_done.get() + pending.len());
+        for ((index, key), (individual, mut statistic)) in pending.into_iter().zip(evaluated.into_iter()) {
+            if let (Some(ref mut cache), Some(key)) = (cache.as_mut(), key) {
+                // cache the statistic without its dungeon.
+                let phenotype = statistic.phenotype.take();
+                cache.insert(key, statistic.clone());
+                statistic.phenotype = phenotype;
+            }
+            results[index] = Some((individual, statistic));
+        }
+        for (index, original, individual, mutation) in duplicates.into_iter() {
+            let mut statistic = results[original].as_ref().unwrap().1.clone();
+            statistic.mutation = mutation;
+            results[index] = Some((individual, statistic));
+        }
+        results.into_iter().map(|r| r.unwrap()).collect()
+    }
+
+    fn end_iteration(&mut self, candidates: &[(G, Statistic)]) {
+        let iteration = self.current_iteration;
+        let population: Vec<&Statistic> = candidates.iter().map(|&(_, ref statistic)| statistic).collect();
+        self.record(iteration, &population[..]);
+        self.save(iteration, &candidates.iter().collect::<Vec<_>>()[..]);
+        self.current_iteration += 1;
+    }
+
+    fn score_novelty(&mut self, candidates: &mut [(G, Statistic)]) {
+        let iteration = self.current_iteration;
+        if let Some(ref mut novelty) = self.novelty {
+            novelty.score(candidates, iteration);
+        }
+    }
+
+    /// sort candidates best first (on `Statistic::ranking`).
+    pub fn rank(&self, mut colony: Vec<(G, Statistic)>, iteration: u32) -> Vec<(G, Statistic)> {
+        // shuffle first, so that ties are broken at random
+        let mut rng = derive_rng(self.rng_seed, &[SHUFFLE, iteration as u64]);
+        shuffle(&mut rng, &mut colony[..]);
+        // sort by ranking (stable, so ties keep their shuffled order)
+        colony.sort_by(|&(_, ref i1), &(_, ref i2)| {
+            match i1.ranking.partial_cmp(&i2.ranking) {
+                Some(ordering) => ordering,
+                None => panic!("{:?} and {:?} could not be ordered.", i1.ranking, i2.ranking)
+            }
+        });
+        colony
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chapters::chapter2::adaptation::{Adaptation};
+    use chapters::chapter2::celloption::{CellOptions};
+    use chapters::chapter2::checkpoint::{Checkpoint};
+    use chapters::chapter2::crossover::{Crossover};
+    use chapters::chapter2::evaluation::{EvaluationFn, check_1x1_rooms, has_entrance_exit};
+    use chapters::chapter2::phenotype::{Seed};
+    use chapters::chapter2::random_seed::{RandomSeed};
+    use chapters::chapter2::selection::{Selection, Replacement};
+    use super::{MuLambda};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn mulambda(threads: usize, chunk_size: usize, rng_seed: u64) -> MuLambda<RandomSeed> {
+        let seed = Seed::new(12, 12,
+                             CellOptions::new(&names(&["floor", "wall", "door", "entrance", "exit"])[..]),
+                             CellOptions::new(&names(&["chest"])[..]),
+                             CellOptions::new(&names(&["orc"])[..]),
+                             0.1);
+        let funcs = vec![box check_1x1_rooms as EvaluationFn, box has_entrance_exit as EvaluationFn];
+        MuLambda::new(threads, chunk_size, 5, 6, 10, 0.5,
+                      Adaptation::Fixed, Selection::Tournament(2), Replacement::Plus,
+                      0.0, Crossover::OnePoint,
+                      RandomSeed::new(&seed), funcs, vec![1.0, 2.0], rng_seed)
+    }
+
+    // the fingerprint and fitness of every candidate, in order.
+    fn winners(mut mulambda: MuLambda<RandomSeed>) -> Vec<(u64, f64)> {
+        mulambda.run().iter().map(|&(_, ref statistic)| (statistic.fingerprint, statistic.fitness)).collect()
+    }
+
+    #[test]
+    fn threads_do_not_change_the_winners() {
+        let single = winners(mulambda(1, 0, 7));
+        assert_eq!(single, winners(mulambda(4, 0, 7)));
+        assert_eq!(single, winners(mulambda(3, 1, 7)));
+    }
+
+    #[test]
+    fn seeds_change_the_winners() {
+        assert!(winners(mulambda(2, 0, 7)) != winners(mulambda(2, 0, 8)));
+    }
+
+    fn checkpoint(engine: &str, strategy: &str, mu: usize, lambda: usize) -> Checkpoint {
+        Checkpoint {
+            engine: engine.to_string(),
+            strategy: strategy.to_string(),
+            mu: mu,
+            lambda: lambda,
+            iteration: 2,
+            rng_seed: 9,
+            mutation: 0.5,
+            strengths: vec![0.5],
+            evaluations: 20,
+            history: vec![],
+            population: vec![],
+            novelty_archive: vec![],
+        }
+    }
+
+    #[test]
+    fn resume_only_fitting_checkpoints() {
+        let mut run = mulambda(1, 0, 7);
+        run.set_run_kind("mu-lambda", "random-seed");
+        assert!(run.resume(checkpoint("nsga2", "random-seed", 6, 10)).is_err());
+        assert!(run.resume(checkpoint("mu-lambda", "grid", 6, 10)).is_err());
+        assert!(run.resume(checkpoint("mu-lambda", "random-seed", 5, 10)).is_err());
+        assert!(run.resume(checkpoint("mu-lambda", "random-seed", 6, 12)).is_err());
+        assert!(run.resume(checkpoint("mu-lambda", "random-seed", 6, 10)).is_ok());
+        assert_eq!(run.rng_seed(), 9);
+        assert_eq!(run.restore().map(|(iteration, _)| iteration), Some(2));
+    }
+}
@@ -0,0 +1,200 @@
+use std::f64;
+use std::cmp::{Ordering};
+
+use chapters::chapter2::celloption::{CellOption};
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::phenotype::{Seed};
+use chapters::chapter2::statistics::{Statistic};
+use chapters::chapter2::descriptors::{BehaviourFn, descriptor, combine};
+use util::config::{Config, ConfigError, ConfigErrors};
+
+/// how dungeons are compared.
+#[derive(Clone, Debug)]
+enum Metric {
+    Hamming(Vec<String>),  // tile by tile; the tile names, for coding
+    Features(Vec<String>), // by these descriptors, each scaled to its range
+}
+
+/// novelty search: an individual is ranked on how different it is
+/// from the others, i.e. the mean distance to its `k` nearest
+/// neighbours among the candidates and an archive of earlier novel
+/// individuals. `fitness_weight` blends the weighted fitness back in
+/// (0.0 is pure novelty, 1.0 pure fitness).
+pub struct Novelty {
+    metric: Metric,
+    k: usize,
+    fitness_weight: f64,
+    archive_additions: usize, // the most novel new individuals archived per iteration
+    archive_size: usize, // the most behaviours archived; the oldest make way
+    archive: Vec<Vec<f64>>,
+}
+
+impl Novelty {
+    /// the `[novelty]` settings, or none if novelty search is not
+    /// enabled. `lambda` is how many individuals are born per
+    /// iteration, the most that can be archived.
+    pub fn from_config(config: &Config, seed: &Seed, lambda: usize) -> Result<Option<Novelty>, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let vars = try!(config.get_optional_table(None, "novelty"));
+        if !try!(config.get_default(&vars, "enabled", false)) {
+            return Ok(None);
+        }
+        let metric = errors.check(config.get_default(&vars, "metric", "hamming".to_string()));
+        let features = errors.check(config.get_default(&vars, "features", vec!["room_count".to_string(),
+                                                                               "path_length".to_string(),
+                                                                               "wall_density".to_string(),
+                                                                               "door_count".to_string()]));
+        let k = errors.check(config.get_default(&vars, "k", 15));
+        let fitness_weight = errors.check(config.get_default(&vars, "fitness_weight", 0.0));
+        let archive_additions = errors.check(config.get_default(&vars, "archive_additions", 2));
+        let archive_size = errors.check(config.get_default(&vars, "archive_size", 250));
+        if let Some(ref features) = features {
+            for (index, name) in features.iter().enumerate() {
+                if let Err(why) = descriptor(&name[..]) {
+                    errors.push(ConfigError::invalid(format!("{}[{}]", vars.key("features"), index), why));
+                }
+            }
+        }
+        let metric = match metric {
+            Some(ref name) if name == "hamming" => Some(Metric::Hamming(seed.tiles.names())),
+            Some(ref name) if name == "features" => features.map(Metric::Features),
+            Some(name) => {
+                let why = format!("unknown metric `{}` (expected hamming or features).", name);
+                errors.push(ConfigError::invalid(vars.key("metric"), why));
+                None
+            }
+            None => None
+        };
+        if let Some(0) = k {
+            errors.push(ConfigError::invalid(vars.key("k"), "must be at least 1.".to_string()));
+        }
+        if let Some(0) = archive_size {
+            errors.push(ConfigError::invalid(vars.key("archive_size"), "must be at least 1.".to_string()));
+        }
+        if let (Some(additions), Some(size)) = (archive_additions, archive_size) {
+            if additions > lambda {
+                let why = format!("must be at most lambda ({}), the dungeons born per iteration.", lambda);
+                errors.push(ConfigError::invalid(vars.key("archive_additions"), why));
+            } else if additions > size {
+                let why = format!("must be at most archive_size ({}).", size);
+                errors.push(ConfigError::invalid(vars.key("archive_additions"), why));
+            }
+        }
+        if let Some(weight) = fitness_weight {
+            if weight < 0.0 || weight > 1.0 {
+                let why = format!("must be between 0.0 and 1.0, not {}.", weight);
+                errors.push(ConfigError::invalid(vars.key("fitness_weight"), why));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Some(Novelty {
+            metric: metric.unwrap(),
+            k: k.unwrap(),
+            fitness_weight: fitness_weight.unwrap(),
+            archive_additions: archive_additions.unwrap(),
+            archive_size: archive_size.unwrap(),
+            archive: vec![],
+        }))
+    }
+
+    /// the behaviours archived so far.
+    pub fn archive(&self) -> &[Vec<f64>] { &self.archive[..] }
+
+    /// carry on with an earlier run's archive.
+    pub fn restore(&mut self, archive: Vec<Vec<f64>>) {
+        self.archive = archive;
+        self.forget();
+    }
+
+    // keep only the newest `archive_size` behaviours.
+    fn forget(&mut self) {
+        if self.archive.len() > self.archive_size {
+            let oldest = self.archive.len() - self.archive_size;
+            self.archive = self.archive[oldest..].to_vec();
+        }
+    }
+
+    /// what is recorded of every individual to compare it by.
+    pub fn behaviour(&self) -> BehaviourFn {
+        match self.metric {
+            Metric::Hamming(ref names) => {
+                // each cell as a number: 0 for no tile, else 1 + the tile's index.
+                let names = names.clone();
+                box move |dungeon: &Dungeon| {
+                    let mut codes = Vec::with_capacity(dungeon.width * dungeon.height);
+                    for x in 0..dungeon.width as u32 {
+                        for y in 0..dungeon.height as u32 {
+                            let code = dungeon.get_cell(x, y).tile.as_ref().and_then(|t| {
+                                let name = t.name();
+                                names.iter().position(|n| *n == name)
+                            }).map(|index| index + 1).unwrap_or(0);
+                            codes.push(code as f64);
+                        }
+                    }
+                    codes
+                }
+            }
+            Metric::Features(ref features) => {
+                // checked by `from_config`.
+                combine(features.iter().map(|name| descriptor(&name[..]).unwrap()).collect())
+            }
+        }
+    }
+
+    fn distance(&self, a: &[f64], b: &[f64], scale: &[f64]) -> f64 {
+        match self.metric {
+            Metric::Hamming(_) => {
+                let different = a.iter().zip(b.iter()).filter(|&(x, y)| x != y).count();
+                different as f64 / a.len().max(1) as f64
+            }
+            Metric::Features(_) => {
+                a.iter().zip(b.iter()).zip(scale.iter()).fold(0.0, |accum, ((x, y), s)| {
+                    accum + ((x - y) / s).powi(2)
+                }).sqrt()
+            }
+        }
+    }
+
+    /// rank the candidates on novelty, and archive the most novel of
+    /// those born on this iteration. novelty is scaled to the most
+    /// novel candidate's, and ranked as a penalty between 0.0 (the
+    /// most novel) and 1.0, so that rankings stay positive (as
+    /// roulette selection needs) and comparable with fitness.
+    pub fn score<G>(&mut self, candidates: &mut [(G, Statistic)], iteration: u32) {
+        let novelties: Vec<f64> = {
+            let behaviours: Vec<&[f64]> = candidates.iter().map(|&(_, ref s)| &s.descriptors[..])
+                .chain(self.archive.iter().map(|b| &b[..]))
+                .collect();
+            // the range of every feature, so that no one feature dominates.
+            let dimensions = behaviours.first().map(|b| b.len()).unwrap_or(0);
+            let scale: Vec<f64> = (0..dimensions).map(|i| {
+                let min = behaviours.iter().map(|b| b[i]).fold(f64::INFINITY, f64::min);
+                let max = behaviours.iter().map(|b| b[i]).fold(f64::NEG_INFINITY, f64::max);
+                if max > min { max - min } else { 1.0 }
+            }).collect();
+            (0..candidates.len()).map(|i| {
+                let mut distances: Vec<f64> = behaviours.iter().enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, b)| self.distance(behaviours[i], b, &scale[..]))
+                    .collect();
+                distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                let nearest = &distances[..self.k.min(distances.len())];
+                nearest.iter().fold(0.0, |accum, d| accum + d) / nearest.len().max(1) as f64
+            }).collect()
+        };
+        let most = novelties.iter().cloned().fold(0.0, f64::max);
+        for (&mut (_, ref mut statistic), &novelty) in candidates.iter_mut().zip(novelties.iter()) {
+            let penalty = if most > 0.0 { 1.0 - novelty / most } else { 0.0 };
+            statistic.novelty = novelty;
+            statistic.ranking = self.fitness_weight * statistic.fitness + (1.0 - self.fitness_weight) * penalty;
+        }
+        let mut newborn: Vec<&(G, Statistic)> = candidates.iter().filter(|&&(_, ref s)| s.iteration == iteration).collect();
+        newborn.sort_by(|&&(_, ref a), &&(_, ref b)| b.novelty.partial_cmp(&a.novelty).unwrap_or(Ordering::Equal));
+        for &&(_, ref statistic) in newborn.iter().take(self.archive_additions) {
+            self.archive.push(statistic.descriptors.clone());
+        }
+        self.forget();
+    }
+}
@@ -0,0 +1,207 @@
+use std::f64;
+use std::cmp::{Ordering};
+
+use chapters::chapter2::genotype::{Genotype};
+use chapters::chapter2::statistics::{Statistic, Generation};
+use chapters::chapter2::stopping::{StopReason};
+use chapters::chapter2::mu_lambda::{MuLambda};
+use chapters::chapter2::selection::{Replacement};
+
+/// multi-objective evolution (NSGA-II). instead of the weighted sum,
+/// individuals are compared on their score vectors: first by Pareto
+/// front, then by crowding distance (more isolated is better). the
+/// generation life-cycle (selection, crossover, mutation) is the one
+/// of the wrapped `MuLambda`.
+pub struct Nsga2<G: Genotype> {
+    mulambda: MuLambda<G>,
+}
+
+// lower scores are better, so `a` dominates `b` when it is no worse
+// everywhere and better somewhere.
+fn dominates(a: &[f64], b: &[f64]) -> bool {
+    let mut better = false;
+    for (x, y) in a.iter().zip(b.iter()) {
+        if x > y {
+            return false;
+        }
+        if x < y {
+            better = true;
+        }
+    }
+    better
+}
+
+/// sort score vectors into Pareto fronts (by index), best front first.
+pub fn fronts(scores: &[&[f64]]) -> Vec<Vec<usize>> {
+    let n = scores.len();
+    let mut dominated_by: Vec<Vec<usize>> = (0..n).map(|_| vec![]).collect();
+    let mut domination_count: Vec<usize> = vec![0; n];
+    for p in 0..n {
+        for q in 0..n {
+            if dominates(scores[p], scores[q]) {
+                dominated_by[p].push(q);
+            } else if dominates(scores[q], scores[p]) {
+                domination_count[p] += 1;
+            }
+        }
+    }
+    let mut result = vec![];
+    let mut current: Vec<usize> = (0..n).filter(|&p| domination_count[p] == 0).collect();
+    while !current.is_empty() {
+        let mut next = vec![];
+        for &p in current.iter() {
+            for &q in dominated_by[p].iter() {
+                domination_count[q] -= 1;
+                if domination_count[q] == 0 {
+                    next.push(q);
+                }
+            }
+        }
+        next.sort();
+        result.push(current);
+        current = next;
+    }
+    result
+}
+
+/// how isolated each member of a front is (the boundary of every
+/// objective is infinitely so).
+pub fn crowding_distance(scores: &[&[f64]], front: &[usize]) -> Vec<f64> {
+    let mut distance = vec![0.0; front.len()];
+    let objectives = front.first().map(|&i| scores[i].len()).unwrap_or(0);
+    for objective in 0..objectives {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| {
+            scores[front[a]][objective].partial_cmp(&scores[front[b]][objective]).unwrap_or(Ordering::Equal)
+        });
+        let min = scores[front[order[0]]][objective];
+        let max = scores[front[order[order.len() - 1]]][objective];
+        distance[order[0]] = f64::INFINITY;
+        distance[order[order.len() - 1]] = f64::INFINITY;
+        if max > min {
+            for k in 1..order.len() - 1 {
+                let gap = scores[front[order[k + 1]]][objective] - scores[front[order[k - 1]]][objective];
+                distance[order[k]] += gap / (max - min);
+            }
+        }
+    }
+    distance
+}
+
+impl<G: Genotype + Clone + Send + 'static> Nsga2<G> {
+    pub fn new(mulambda: MuLambda<G>) -> Nsga2<G> {
+        Nsga2 {
+            mulambda: mulambda
+        }
+    }
+
+    /// a summary of every generation (see `MuLambda::history`).
+    pub fn history(&self) -> &[Generation] { self.mulambda.history() }
+
+    /// why the run ended (see `MuLambda::stop_reason`).
+    pub fn stop_reason(&self) -> StopReason { self.mulambda.stop_reason() }
+
+    /// run every iteration and return the Pareto front of the last
+    /// one, most isolated first.
+    pub fn run(&mut self) -> Vec<(G, Statistic)> {
+        let (mut candidates, mut ranks, first) = match self.mulambda.restore() {
+            Some((iteration, population)) => {
+                let (candidates, ranks) = self.sort(population);
+                (candidates, ranks, iteration)
+            }
+            None => {
+                let primer = self.mulambda.initialize();
+                let (candidates, ranks) = self.sort(self.mulambda.evaluate(primer, 0));
+                self.record(0, &candidates[..]);
+                (candidates, ranks, 1)
+            }
+        };
+        for iteration in first..self.mulambda.iterations() {
+            if self.mulambda.stopped() {
+                break;
+            }
+            let mu = self.mulambda.mu();
+            let population: Vec<(G, Statistic)> = candidates.into_iter().take(mu).collect();
+            let offspring = self.mulambda.breed(&population[..], &ranks[..mu.min(ranks.len())], iteration);
+            let offspring = self.mulambda.evaluate_offspring(offspring, iteration);
+            let combined = match *self.mulambda.replacement() {
+                Replacement::Plus => population.into_iter().chain(offspring.into_iter()).collect(),
+                Replacement::Comma => offspring,
+            };
+            let (sorted, sorted_ranks) = self.sort(combined);
+            candidates = sorted;
+            ranks = sorted_ranks;
+            self.record(iteration, &candidates[..]);
+        }
+        candidates.into_iter().zip(ranks.into_iter()).take_while(|&(_, rank)| rank == 0.0).map(|(c, _)| c).collect()
+    }
+
+    fn record(&mut self, iteration: u32, candidates: &[(G, Statistic)]) {
+        let population: Vec<&Statistic> = candidates.iter().map(|&(_, ref statistic)| statistic).collect();
+        self.mulambda.record(iteration, &population[..]);
+        self.mulambda.save(iteration, &candidates.iter().collect::<Vec<_>>()[..]);
+    }
+
+    // order candidates by front and then crowding distance, along with
+    // the front each one is on.
+    fn sort(&self, candidates: Vec<(G, Statistic)>) -> (Vec<(G, Statistic)>, Vec<f64>) {
+        let order: Vec<(usize, usize, f64)> = {
+            let scores: Vec<&[f64]> = candidates.iter().map(|&(_, ref statistic)| &statistic.scores[..]).collect();
+            let mut order = vec![];
+            for (rank, front) in fronts(&scores[..]).into_iter().enumerate() {
+                let distance = crowding_distance(&scores[..], &front[..]);
+                let mut members: Vec<(usize, usize, f64)> = front.into_iter().zip(distance.into_iter())
+                    .map(|(index, d)| (index, rank, d))
+                    .collect();
+                members.sort_by(|&(_, _, a), &(_, _, b)| b.partial_cmp(&a).unwrap_or(Ordering::Equal));
+                order.extend(members.into_iter());
+            }
+            order
+        };
+        let mut slots: Vec<Option<(G, Statistic)>> = candidates.into_iter().map(|c| Some(c)).collect();
+        let ranks = order.iter().map(|&(_, rank, _)| rank as f64).collect();
+        let sorted = order.into_iter().map(|(index, _, _)| slots[index].take().unwrap()).collect();
+        (sorted, ranks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64;
+    use super::{fronts, crowding_distance};
+
+    #[test]
+    fn fronts_are_sorted_by_domination() {
+        let scores: Vec<Vec<f64>> = vec![vec![1.0, 4.0], vec![2.0, 2.0], vec![4.0, 1.0], vec![3.0, 3.0], vec![5.0, 5.0], vec![2.0, 2.0]];
+        let scores: Vec<&[f64]> = scores.iter().map(|s| &s[..]).collect();
+        assert_eq!(fronts(&scores[..]), vec![vec![0, 1, 2, 5], vec![3], vec![4]]);
+    }
+
+    #[test]
+    fn every_index_is_in_one_front() {
+        let scores: Vec<Vec<f64>> = (0..20).map(|i| vec![(i * 7 % 11) as f64, (i * 3 % 5) as f64]).collect();
+        let scores: Vec<&[f64]> = scores.iter().map(|s| &s[..]).collect();
+        let mut all: Vec<usize> = fronts(&scores[..]).into_iter().flat_map(|front| front.into_iter()).collect();
+        all.sort();
+        assert_eq!(all, (0..20).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn boundaries_are_infinitely_crowded() {
+        let scores: Vec<Vec<f64>> = vec![vec![0.0, 4.0], vec![1.0, 3.0], vec![3.0, 1.0], vec![4.0, 0.0]];
+        let scores: Vec<&[f64]> = scores.iter().map(|s| &s[..]).collect();
+        let distance = crowding_distance(&scores[..], &[0, 1, 2, 3]);
+        assert_eq!(distance[0], f64::INFINITY);
+        assert_eq!(distance[3], f64::INFINITY);
+        // each inner point spans 3/4 of both objectives.
+        assert!((distance[1] - 1.5).abs() < 1e-9);
+        assert!((distance[2] - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_lone_point_is_a_boundary() {
+        let scores: Vec<Vec<f64>> = vec![vec![1.0, 1.0]];
+        let scores: Vec<&[f64]> = scores.iter().map(|s| &s[..]).collect();
+        assert_eq!(crowding_distance(&scores[..], &[0]), vec![f64::INFINITY]);
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::{Path};
+use std::io::{Write};
+use std::fs::{File, create_dir_all};
+use rustc_serialize::json::{as_pretty_json};
+
+use chapters::chapter2::celloption::{CellOption};
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::statistics::{Statistic, Generation};
+use chapters::chapter2::stopping::{StopReason};
+use util::offscreen::{OffscreenSpriteSheet, Canvas, save_canvas};
+
+/// a serialisable snapshot of a winning dungeon. rows are stored
+/// top to bottom, the same way the viewer draws them.
+#[derive(RustcEncodable, Debug)]
+struct DungeonRecord {
+    iteration: u32,
+    fitness: f64,
+    scores: Vec<f64>, // per evaluation, in configuration order
+    mutation: f64, // the mutation strength it was made with
+    width: usize,
+    height: usize,
+    tiles: Vec<Vec<Option<String>>>,
+    occupants: Vec<Vec<Option<String>>>,
+}
+
+impl DungeonRecord {
+    fn new(dungeon: &Dungeon, statistic: &Statistic) -> DungeonRecord {
+        let rows = |f: &Fn(u32, u32) -> Option<String>| -> Vec<Vec<Option<String>>> {
+            (0..dungeon.height as u32).map(|y| {
+                (0..dungeon.width as u32).map(|x| f(x, y)).collect()
+            }).collect()
+        };
+        let tiles = rows(&|x, y| dungeon.get_cell(x, y).tile.as_ref().map(|t| t.name()));
+        let occupants = rows(&|x, y| dungeon.get_cell(x, y).occupant.as_ref().map(|o| o.name()));
+        DungeonRecord {
+            iteration: statistic.iteration,
+            fitness: statistic.fitness,
+            scores: statistic.scores.clone(),
+            mutation: statistic.mutation,
+            width: dungeon.width,
+            height: dungeon.height,
+            tiles: tiles,
+            occupants: occupants,
+        }
+    }
+}
+
+/// make sure the output directory exists before writing into it.
+pub fn prepare_directory(out: &Path) {
+    match create_dir_all(out) {
+        Err(why) => panic!("Could not create output directory {}: {}", out.display(), why),
+        _ => ()
+    }
+}
+
+/// write a single winner (dungeon and statistic) as JSON.
+pub fn write_winner(path: &Path, dungeon: &Dungeon, statistic: &Statistic) {
+    let record = DungeonRecord::new(dungeon, statistic);
+    let mut file = match File::create(path) {
+        Ok(f) => f,
+        Err(why) => panic!("Could not create {}: {}", path.display(), why)
+    };
+    match write!(file, "{}\n", as_pretty_json(&record)) {
+        Err(why) => panic!("Could not write {}: {}", path.display(), why),
+        _ => ()
+    }
+}
+
+/// how run statistics are written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogFormat {
+    Csv,
+    Json,
+}
+
+impl LogFormat {
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            LogFormat::Csv => "csv",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(RustcEncodable, Debug)]
+struct HistoryRecord<'a> {
+    evaluations: &'a [String],
+    generations: &'a [Generation],
+    stopped: &'a StopReason,
+}
+
+/// write a summary of every generation of a run, with the mean
+/// scores labelled by evaluation name, and why it stopped (in CSV, a
+/// `stopped` column that is only filled in on the last row).
+pub fn write_history(path: &Path, format: &LogFormat, evaluations: &[String], history: &[Generation], stopped: &StopReason) {
+    let contents = match *format {
+        LogFormat::Csv => {
+            let mut header = vec!["iteration", "min", "mean", "max", "stddev", "diversity", "mutation", "seconds", "throughput"];
+            header.extend(evaluations.iter().map(|name| &name[..]));
+            header.push("stopped");
+            let mut csv = format!("{}\n", header.join(","));
+            for (index, g) in history.iter().enumerate() {
+                let mut row: Vec<String> = vec![g.iteration as f64, g.min, g.mean, g.max, g.stddev, g.diversity, g.mutation, g.seconds, g.throughput]
+                    .into_iter()
+                    .map(|value| value.to_string())
+                    .collect();
+                row.extend(g.scores.iter().map(|score| score.to_string()));
+                row.push(if index + 1 == history.len() { format!("{:?}", stopped) } else { String::new() });
+                csv.push_str(&format!("{}\n", row.join(","))[..]);
+            }
+            csv
+        }
+        LogFormat::Json => {
+            let record = HistoryRecord {
+                evaluations: evaluations,
+                generations: history,
+                stopped: stopped,
+            };
+            format!("{}\n", as_pretty_json(&record))
+        }
+    };
+    let mut file = match File::create(path) {
+        Ok(f) => f,
+        Err(why) => panic!("Could not create {}: {}", path.display(), why)
+    };
+    match write!(file, "{}", contents) {
+        Err(why) => panic!("Could not write {}: {}", path.display(), why),
+        _ => ()
+    }
+}
+
+/// draw a dungeon the way the viewer does (first animation frame).
+pub fn render_dungeon(spritesheet: &OffscreenSpriteSheet, dungeon: &Dungeon, tile_width: u32, tile_height: u32) -> Canvas {
+    let mut canvas = Canvas::new(dungeon.width as u32 * tile_width, dungeon.height as u32 * tile_height);
+    for y in 0..dungeon.height as u32 {
+        for x in 0..dungeon.width as u32 {
+            let cell = dungeon.get_cell(x, y);
+            let px = x * tile_width;
+            let py = y * tile_height;
+            let drawn = match cell.tile {
+                Some(ref val) => spritesheet.draw(&mut canvas, &val.name()[..], px, py, 0),
+                None => false
+            };
+            if !drawn {
+                OffscreenSpriteSheet::missing(&mut canvas, px, py, tile_width, tile_height);
+            }
+            if let Some(ref val) = cell.occupant {
+                spritesheet.draw(&mut canvas, &val.name()[..], px, py, 0);
+            }
+        }
+    }
+    canvas
+}
+
+/// render a dungeon and save it; the format follows the extension.
+pub fn write_thumbnail(path: &Path, spritesheet: &OffscreenSpriteSheet, dungeon: &Dungeon, tile_width: u32, tile_height: u32) {
+    let canvas = render_dungeon(spritesheet, dungeon, tile_width, tile_height);
+    save_canvas(&canvas, path);
+}
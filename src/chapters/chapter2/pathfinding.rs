@@ -0,0 +1,320 @@
+use std::collections::{BinaryHeap, VecDeque};
+use std::cmp::{Ordering};
+
+use chapters::chapter2::dungeon::{Dungeon, DungeonCells, Surrounding};
+use chapters::chapter2::phenotype::{Seed};
+use util::config::{Config, ConfigError, ConfigErrors, Section};
+
+/// how paths are searched for: which neighbours a step can reach, and
+/// which tiles can be walked on. the start and goal can always be
+/// stood on, whatever their tiles. every step costs the same, diagonal
+/// or not. a path is the list of cells from start to goal, both
+/// included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pathfinder {
+    around: Surrounding,
+    passable: Vec<String>, // tile names
+}
+
+// an open cell for A*, ordered so that the heap pops the lowest
+// estimate first.
+#[derive(PartialEq, Eq)]
+struct Open {
+    estimate: u32, // steps so far plus the heuristic
+    steps: u32,
+    cell: (u32, u32),
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Open) -> Ordering {
+        // on a tie, the one further along.
+        match other.estimate.cmp(&self.estimate) {
+            Ordering::Equal => match self.steps.cmp(&other.steps) {
+                Ordering::Equal => self.cell.cmp(&other.cell),
+                ordering => ordering
+            },
+            ordering => ordering
+        }
+    }
+}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Open) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Default for Pathfinder {
+    /// cardinal steps over floors, doors, the entrance and the exit.
+    fn default() -> Pathfinder {
+        let passable = ["floor", "door", "entrance", "exit"].iter().map(|tile| tile.to_string()).collect();
+        Pathfinder::new(Surrounding::Cardinal, passable)
+    }
+}
+
+impl Pathfinder {
+    pub fn new(around: Surrounding, passable: Vec<String>) -> Pathfinder {
+        Pathfinder {
+            around: around,
+            passable: passable,
+        }
+    }
+
+    /// `moves` ("cardinal" or "all") and `passable` (names of the
+    /// seed's tiles), both optional.
+    pub fn from_config(config: &Config, vars: &Section, seed: &Seed) -> Result<Pathfinder, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let default = Pathfinder::default();
+        let around = match errors.check(config.get_default(vars, "moves", "cardinal".to_string())) {
+            Some(ref name) if name == "cardinal" => Some(Surrounding::Cardinal),
+            Some(ref name) if name == "all" => Some(Surrounding::AllDirections),
+            Some(name) => {
+                let why = format!("unknown moves `{}` (expected cardinal or all).", name);
+                errors.push(ConfigError::invalid(vars.key("moves"), why));
+                None
+            }
+            None => None
+        };
+        let passable = if vars.contains("passable") {
+            errors.check(config.get_array(vars, "passable"))
+        } else {
+            Some(default.passable)
+        };
+        if let Some(ref passable) = passable {
+            let tiles = seed.tiles.names();
+            for (index, name) in passable.iter().enumerate() {
+                if !tiles.contains(name) {
+                    let why = format!("unknown tile `{}` (expected one of {}).", name, tiles.join(", "));
+                    errors.push(ConfigError::invalid(format!("{}[{}]", vars.key("passable"), index), why));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Pathfinder::new(around.unwrap(), passable.unwrap()))
+    }
+
+    /// can the cell be walked on?
+    pub fn passable(&self, dungeon: &Dungeon, x: u32, y: u32) -> bool {
+        self.passable.iter().any(|tile| dungeon.has_attribute(x, y, &tile[..]))
+    }
+
+    // the cells one step away that are passable (or the goal).
+    fn neighbours(&self, dungeon: &Dungeon, (x, y): (u32, u32), goal: (u32, u32)) -> Vec<(u32, u32)> {
+        self.around.offsets().iter()
+            .map(|&(dx, dy)| (x as i32 + dx, y as i32 + dy))
+            .filter(|&(nx, ny)| dungeon.in_bounds(nx, ny))
+            .map(|(nx, ny)| (nx as u32, ny as u32))
+            .filter(|&(nx, ny)| (nx, ny) == goal || self.passable(dungeon, nx, ny))
+            .collect()
+    }
+
+    // the fewest steps between two cells, were there no obstacles.
+    fn heuristic(&self, (x, y): (u32, u32), (gx, gy): (u32, u32)) -> u32 {
+        let (dx, dy) = ((x as i32 - gx as i32).abs() as u32, (y as i32 - gy as i32).abs() as u32);
+        match self.around {
+            Surrounding::Cardinal => dx + dy,
+            Surrounding::AllDirections => dx.max(dy),
+        }
+    }
+
+    /// the shortest path by breadth-first search, if there is one.
+    pub fn bfs(&self, dungeon: &Dungeon, start: (u32, u32), goal: (u32, u32)) -> Option<Vec<(u32, u32)>> {
+        let mut previous: Vec<Vec<Option<(u32, u32)>>> = vec![vec![None; dungeon.height]; dungeon.width];
+        let mut pending = VecDeque::new();
+        previous[start.0 as usize][start.1 as usize] = Some(start);
+        pending.push_back(start);
+        while let Some(cell) = pending.pop_front() {
+            if cell == goal {
+                return Some(walk_back(&previous, start, goal));
+            }
+            for next in self.neighbours(dungeon, cell, goal).into_iter() {
+                if previous[next.0 as usize][next.1 as usize].is_none() {
+                    previous[next.0 as usize][next.1 as usize] = Some(cell);
+                    pending.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// the shortest path by A* search, if there is one. it finds paths
+    /// as short as `bfs` does, usually looking at fewer cells.
+    pub fn astar(&self, dungeon: &Dungeon, start: (u32, u32), goal: (u32, u32)) -> Option<Vec<(u32, u32)>> {
+        let mut previous: Vec<Vec<Option<(u32, u32)>>> = vec![vec![None; dungeon.height]; dungeon.width];
+        let mut best: Vec<Vec<Option<u32>>> = vec![vec![None; dungeon.height]; dungeon.width];
+        let mut open = BinaryHeap::new();
+        previous[start.0 as usize][start.1 as usize] = Some(start);
+        best[start.0 as usize][start.1 as usize] = Some(0);
+        open.push(Open { estimate: self.heuristic(start, goal), steps: 0, cell: start });
+        while let Some(Open { steps, cell, .. }) = open.pop() {
+            if cell == goal {
+                return Some(walk_back(&previous, start, goal));
+            }
+            // a shorter way here was found after this one was queued.
+            if best[cell.0 as usize][cell.1 as usize].map_or(false, |b| b < steps) {
+                continue;
+            }
+            for next in self.neighbours(dungeon, cell, goal).into_iter() {
+                let (nx, ny) = (next.0 as usize, next.1 as usize);
+                if best[nx][ny].map_or(true, |b| steps + 1 < b) {
+                    best[nx][ny] = Some(steps + 1);
+                    previous[nx][ny] = Some(cell);
+                    open.push(Open { estimate: steps + 1 + self.heuristic(next, goal), steps: steps + 1, cell: next });
+                }
+            }
+        }
+        None
+    }
+
+    /// the shortest path from the entrance to the exit, if the dungeon
+    /// has both and they are connected.
+    pub fn entrance_to_exit(&self, dungeon: &Dungeon) -> Option<Vec<(u32, u32)>> {
+        entrance_and_exit(dungeon).and_then(|(start, goal)| self.astar(dungeon, start, goal))
+    }
+}
+
+/// where the (first) entrance and exit are, if there are both.
+pub fn entrance_and_exit(dungeon: &Dungeon) -> Option<((u32, u32), (u32, u32))> {
+    let find = |name| DungeonCells::new(dungeon).find(|cell| cell.has_attribute(name)).map(|cell| (cell.x, cell.y));
+    match (find("entrance"), find("exit")) {
+        (Some(start), Some(goal)) => Some((start, goal)),
+        _ => None
+    }
+}
+
+// follow the trail of previous cells back from the goal.
+fn walk_back(previous: &[Vec<Option<(u32, u32)>>], start: (u32, u32), goal: (u32, u32)) -> Vec<(u32, u32)> {
+    let mut path = vec![goal];
+    let mut cell = goal;
+    while cell != start {
+        cell = previous[cell.0 as usize][cell.1 as usize].unwrap();
+        path.push(cell);
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path};
+    use toml::{Value};
+
+    use chapters::chapter2::ascii_map::{AsciiMap};
+    use chapters::chapter2::celloption::{CellOptions};
+    use chapters::chapter2::dungeon::{Dungeon, Surrounding};
+    use chapters::chapter2::genotype::{Genotype};
+    use chapters::chapter2::phenotype::{Seed};
+    use chapters::chapter2::random_seed::{RandomSeed};
+    use util::config::{Config};
+    use util::util::{derive_rng};
+    use super::{Pathfinder, entrance_and_exit};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn seed() -> Seed {
+        Seed::new(12, 12,
+                  CellOptions::new(&names(&["floor", "wall", "entrance", "exit", "door"])[..]),
+                  CellOptions::new(&names(&[])[..]),
+                  CellOptions::new(&names(&["monster"])[..]),
+                  0.0)
+    }
+
+    fn config() -> Config {
+        Config::new(&Path::new(env!("CARGO_MANIFEST_DIR")).join("config").join("chapter2.toml")).unwrap()
+    }
+
+    fn dungeon(map: &str) -> Dungeon {
+        AsciiMap::new(&config(), &seed()).unwrap().from_str(map).unwrap()
+    }
+
+    // the number of steps along the entrance-to-exit path.
+    fn steps(pathfinder: &Pathfinder, dungeon: &Dungeon) -> Option<usize> {
+        pathfinder.entrance_to_exit(dungeon).map(|path| path.len() - 1)
+    }
+
+    const MAZE: &'static str = "XXXXXXX\n\
+                                >___X_X\n\
+                                XXX_X_X\n\
+                                X___d_X\n\
+                                X_XXX_<\n\
+                                XXXXXXX\n";
+
+    #[test]
+    fn paths_go_around_walls() {
+        let dungeon = dungeon(MAZE);
+        let path = Pathfinder::default().entrance_to_exit(&dungeon).unwrap();
+        assert_eq!(path.first(), Some(&(0, 1)));
+        assert_eq!(path.last(), Some(&(6, 4)));
+        assert_eq!(path.len() - 1, 9);
+        // every step is to a neighbour, over a passable cell.
+        for pair in path.windows(2) {
+            let (dx, dy) = ((pair[0].0 as i32 - pair[1].0 as i32).abs(), (pair[0].1 as i32 - pair[1].1 as i32).abs());
+            assert_eq!(dx + dy, 1);
+            assert!(Pathfinder::default().passable(&dungeon, pair[1].0, pair[1].1));
+        }
+    }
+
+    #[test]
+    fn walls_and_closed_doors_block() {
+        let walled = dungeon(">_X_<\n");
+        assert_eq!(steps(&Pathfinder::default(), &walled), None);
+        let door = dungeon(">_d_<\n");
+        assert_eq!(steps(&Pathfinder::default(), &door), Some(4));
+        let closed = Pathfinder::new(Surrounding::Cardinal, names(&["floor"]));
+        assert_eq!(steps(&closed, &door), None);
+    }
+
+    #[test]
+    fn start_and_goal_need_not_be_passable() {
+        let floors = Pathfinder::new(Surrounding::Cardinal, names(&["floor"]));
+        assert_eq!(steps(&floors, &dungeon(">___<\n")), Some(4));
+        assert_eq!(steps(&floors, &dungeon("><\n")), Some(1));
+    }
+
+    #[test]
+    fn diagonal_moves_cut_corners() {
+        let dungeon = dungeon(">__\n\
+                               ___\n\
+                               __<\n");
+        assert_eq!(steps(&Pathfinder::default(), &dungeon), Some(4));
+        let all = Pathfinder::new(Surrounding::AllDirections, names(&["floor", "entrance", "exit"]));
+        assert_eq!(steps(&all, &dungeon), Some(2));
+    }
+
+    #[test]
+    fn astar_and_bfs_agree_on_length() {
+        let genotype = RandomSeed::new(&seed());
+        let pathfinders = [Pathfinder::default(),
+                           Pathfinder::new(Surrounding::AllDirections, names(&["floor", "door"]))];
+        let mut maps = vec![dungeon(MAZE)];
+        for i in 0..40 {
+            maps.push(genotype.initialize(&mut derive_rng(i, &[])).generate());
+        }
+        for dungeon in maps.iter() {
+            if let Some((start, goal)) = entrance_and_exit(dungeon) {
+                for pathfinder in pathfinders.iter() {
+                    let astar = pathfinder.astar(dungeon, start, goal).map(|path| path.len());
+                    let bfs = pathfinder.bfs(dungeon, start, goal).map(|path| path.len());
+                    assert_eq!(astar, bfs);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_passable_tiles_are_rejected() {
+        let mut config = config();
+        config.set("pathfinding.passable", Value::Array(vec![Value::String("floor".to_string()),
+                                                              Value::String("lava".to_string())])).unwrap();
+        let vars = config.get_table(None, "pathfinding").unwrap();
+        let errors = Pathfinder::from_config(&config, &vars, &seed()).unwrap_err();
+        assert!(errors.to_string().contains("pathfinding.passable[1]"));
+        config.set("pathfinding.passable", Value::Array(vec![Value::String("door".to_string())])).unwrap();
+        let vars = config.get_table(None, "pathfinding").unwrap();
+        assert!(Pathfinder::from_config(&config, &vars, &seed()).is_ok());
+    }
+}
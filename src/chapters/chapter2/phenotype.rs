@@ -1,12 +1,12 @@
-use chapter2::dungeon::{Dungeon};
-use chapter2::statistics::{Statistic};
-use chapter2::celloption::{CellOptions, CellOption, Tile, Item, Occupant};
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::statistics::{Statistic};
+use chapters::chapter2::celloption::{CellOptions, CellOption, Tile, Item, Occupant};
 use util::util::{odds};
 
 use rand::{Rng};
 
 /// Seed holds all the information necessary to generate the phenotype.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Seed {
     pub width: u32,
     pub height: u32,
@@ -0,0 +1,63 @@
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::genotype::{Genotype, to_json, from_json};
+use chapters::chapter2::phenotype::{Seed};
+use util::util::{derive_rng};
+
+use rand::{Rng};
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct RandomSeed {
+    seed: Seed,
+    rng_seed: u64, // the whole genotype: the dungeon is drawn from this
+}
+
+impl RandomSeed {
+    pub fn new(seed: &Seed) -> RandomSeed {
+        RandomSeed {
+            seed: seed.clone(),
+            rng_seed: 0,
+        }
+    }
+}
+
+impl Genotype for RandomSeed {
+    fn initialize<R: Rng>(&self, rng: &mut R) -> RandomSeed {
+        RandomSeed {
+            seed: self.seed.clone(),
+            rng_seed: rng.gen(),
+        }
+    }
+
+    fn mutate<R: Rng>(&mut self, rng: &mut R, _: f64) {
+        self.rng_seed = rng.gen();
+    }
+
+    fn encode_json(&self) -> String {
+        to_json(self)
+    }
+
+    fn decode_json(&self, json: &str) -> Result<RandomSeed, String> {
+        from_json(json)
+    }
+
+    fn encode_genome(&self) -> String {
+        to_json(&self.rng_seed)
+    }
+
+    fn generate(&self) -> Dungeon {
+        let mut rng = derive_rng(self.rng_seed, &[]);
+        let w = self.seed.width;
+        let h = self.seed.height;
+        let mut dungeon = Dungeon::new(w, h, None);
+        for i in 0..dungeon.width as u32 {
+            for j in 0..dungeon.height as u32 {
+                let tile = self.seed.tiles.choose(&mut rng).clone();
+                if let Some(occupant) = self.seed.random_occupant(&mut rng, &tile) {
+                    dungeon.set_occupant(i, j, &occupant);
+                }
+                dungeon.set_tile(i, j, &tile);
+            }
+        }
+        dungeon.clone()
+    }
+}
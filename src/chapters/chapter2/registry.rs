@@ -0,0 +1,150 @@
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::genotype::{AnyGenotype};
+use chapters::chapter2::evaluation::{EvaluationFn, check_1x1_rooms, has_entrance_exit, doors_are_useful, rooms_are_accessible, room_count, door_count, exit_reachable, path_length};
+use chapters::chapter2::phenotype::{Seed};
+use chapters::chapter2::random_seed::{RandomSeed};
+use chapters::chapter2::list_of_walls::{ListOfWalls};
+use chapters::chapter2::wall_patterns::{WallPatterns};
+use chapters::chapter2::desirable_properties::{DesirableProperties};
+use util::config::{Config, ConfigErrors, Section};
+
+pub type GenotypeConstructor = Box<Fn(&Config, &Seed) -> Result<Box<AnyGenotype>, ConfigErrors>>;
+
+/// builds an evaluation from its `[evaluations.<name>]` table (which
+/// reads as empty when it is not there), for dungeons of the seed.
+pub type EvaluationConstructor = Box<Fn(&Config, &Section, &Seed) -> Result<EvaluationFn, ConfigErrors>>;
+
+/// everything chapter 2 can look up by name in its configuration.
+pub struct Registry {
+    pub genotypes: GenotypeRegistry,
+    pub evaluations: EvaluationRegistry,
+}
+
+impl Registry {
+    pub fn with_defaults() -> Registry {
+        Registry {
+            genotypes: GenotypeRegistry::with_defaults(),
+            evaluations: EvaluationRegistry::with_defaults(),
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    section: Option<String>, // the configuration table the genotype reads
+    constructor: GenotypeConstructor,
+}
+
+/// genotypes by name, as used by `[mu-lambda] strategy`.
+pub struct GenotypeRegistry {
+    entries: Vec<Entry>
+}
+
+impl GenotypeRegistry {
+    pub fn new() -> GenotypeRegistry {
+        GenotypeRegistry {
+            entries: vec![]
+        }
+    }
+
+    /// the genotypes that come with chapter 2.
+    pub fn with_defaults() -> GenotypeRegistry {
+        let mut registry = GenotypeRegistry::new();
+        registry.register("RandomSeed", None, box |_: &Config, seed: &Seed| {
+            Ok(box RandomSeed::new(seed) as Box<AnyGenotype>)
+        });
+        registry.register("ListOfWalls", Some("list-of-walls"), box |config: &Config, seed: &Seed| {
+            ListOfWalls::new(config, seed).map(|g| box g as Box<AnyGenotype>)
+        });
+        registry.register("WallPatterns", Some("wallpatterns"), box |config: &Config, seed: &Seed| {
+            WallPatterns::new(config, seed).map(|g| box g as Box<AnyGenotype>)
+        });
+        registry.register("DesirableProperties", Some("desirable_patterns"), box |config: &Config, seed: &Seed| {
+            DesirableProperties::new(config, seed).map(|g| box g as Box<AnyGenotype>)
+        });
+        registry
+    }
+
+    /// add a genotype (or replace one of the same name). `section` is
+    /// the configuration table it reads, which `check-config` checks
+    /// whenever it is present.
+    pub fn register(&mut self, name: &str, section: Option<&str>, constructor: GenotypeConstructor) {
+        self.entries.retain(|entry| entry.name != name);
+        self.entries.push(Entry {
+            name: name.to_string(),
+            section: section.map(|s| s.to_string()),
+            constructor: constructor,
+        });
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|entry| &entry.name[..]).collect()
+    }
+
+    /// the sections read by registered genotypes, with their names.
+    pub fn sections(&self) -> Vec<(&str, &str)> {
+        self.entries.iter().filter_map(|entry| {
+            entry.section.as_ref().map(|section| (&entry.name[..], &section[..]))
+        }).collect()
+    }
+
+    /// construct a genotype by name. an unknown name is an error that
+    /// lists the valid ones.
+    pub fn create(&self, name: &str, config: &Config, seed: &Seed) -> Result<Result<Box<AnyGenotype>, ConfigErrors>, String> {
+        match self.entries.iter().find(|entry| entry.name == name) {
+            Some(entry) => Ok((entry.constructor)(config, seed)),
+            None => Err(format!("unknown strategy `{}` (expected one of {}).", name, self.names().join(", ")))
+        }
+    }
+}
+
+/// evaluation functions by name, as used by `[mu-lambda] evaluations`.
+pub struct EvaluationRegistry {
+    entries: Vec<(String, EvaluationConstructor)>
+}
+
+impl EvaluationRegistry {
+    pub fn new() -> EvaluationRegistry {
+        EvaluationRegistry {
+            entries: vec![]
+        }
+    }
+
+    /// the evaluations that come with chapter 2.
+    pub fn with_defaults() -> EvaluationRegistry {
+        let mut registry = EvaluationRegistry::new();
+        registry.register_fn("check_1x1_rooms", check_1x1_rooms);
+        registry.register_fn("has_entrance_exit", has_entrance_exit);
+        registry.register_fn("doors_are_useful", doors_are_useful);
+        registry.register_fn("rooms_are_accessible", rooms_are_accessible);
+        registry.register("room_count", box room_count);
+        registry.register("door_count", box door_count);
+        registry.register("exit_reachable", box exit_reachable);
+        registry.register("path_length", box path_length);
+        registry
+    }
+
+    /// add an evaluation (or replace one of the same name).
+    pub fn register(&mut self, name: &str, constructor: EvaluationConstructor) {
+        self.entries.retain(|&(ref n, _)| n != name);
+        self.entries.push((name.to_string(), constructor));
+    }
+
+    /// add an evaluation that takes no settings.
+    pub fn register_fn(&mut self, name: &str, f: fn(&Dungeon) -> f64) {
+        self.register(name, box move |_: &Config, _: &Section, _: &Seed| Ok(box f as EvaluationFn));
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|&(ref name, _)| &name[..]).collect()
+    }
+
+    /// construct an evaluation of the given type from its settings. an
+    /// unknown type is an error that lists the valid ones.
+    pub fn create(&self, kind: &str, config: &Config, vars: &Section, seed: &Seed) -> Result<Result<EvaluationFn, ConfigErrors>, String> {
+        match self.entries.iter().find(|&&(ref name, _)| name == kind) {
+            Some(&(_, ref constructor)) => Ok(constructor(config, vars, seed)),
+            None => Err(format!("unknown evaluation `{}` (expected one of {}).", kind, self.names().join(", ")))
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use rand::{Rng};
+
+use util::config::{Config, ConfigError, ConfigErrors, Section};
+
+/// how parents are picked from the population.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    Truncation,        // every one of the mu elites in turn
+    Tournament(usize), // the best of k drawn at random
+    Roulette,          // proportional to 1 / (1 + fitness)
+    Rank,              // proportional to position (best ranks highest)
+}
+
+/// what the next population is chosen from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Replacement {
+    Plus,  // (mu + lambda): parents and offspring compete
+    Comma, // (mu, lambda): only offspring survive
+}
+
+// pick an index with probability proportional to its weight.
+fn spin<R: Rng>(rng: &mut R, weights: &[f64]) -> usize {
+    let total = weights.iter().fold(0.0, |accum, &w| accum + w);
+    let mut ball = rng.gen::<f64>() * total;
+    for (index, &weight) in weights.iter().enumerate() {
+        if ball < weight {
+            return index;
+        }
+        ball -= weight;
+    }
+    weights.len() - 1
+}
+
+impl Selection {
+    /// `selection` (default "truncation") and, for tournaments,
+    /// `tournament_size` (default 3).
+    pub fn from_config(config: &Config, vars: &Section) -> Result<Selection, ConfigErrors> {
+        let name = try!(config.get_default(vars, "selection", "truncation".to_string()));
+        match &name[..] {
+            "truncation" => Ok(Selection::Truncation),
+            "tournament" => {
+                let size: usize = try!(config.get_default(vars, "tournament_size", 3));
+                if size == 0 {
+                    return Err(ConfigError::invalid(vars.key("tournament_size"), "must be at least 1.".to_string()).into());
+                }
+                Ok(Selection::Tournament(size))
+            }
+            "roulette" => Ok(Selection::Roulette),
+            "rank" => Ok(Selection::Rank),
+            _ => {
+                let why = format!("unknown selection `{}` (expected one of truncation, tournament, roulette, rank).", name);
+                Err(ConfigError::invalid(vars.key("selection"), why).into())
+            }
+        }
+    }
+
+    /// pick `n` parents (by index) from a population sorted best
+    /// first, of which the first `mu` are the elites.
+    pub fn select<R: Rng>(&self, rng: &mut R, fitnesses: &[f64], mu: usize, n: usize) -> Vec<usize> {
+        let size = fitnesses.len();
+        match *self {
+            Selection::Truncation => {
+                let elites = if mu < size { mu } else { size };
+                (0..n).map(|i| i % elites).collect()
+            }
+            Selection::Tournament(k) => {
+                // the population is sorted, so the lowest index wins.
+                (0..n).map(|_| (0..k).map(|_| rng.gen_range(0, size)).min().unwrap()).collect()
+            }
+            Selection::Roulette => {
+                // fitness is a penalty, so lower is likelier.
+                let weights: Vec<f64> = fitnesses.iter().map(|&f| 1.0 / (1.0 + f.max(0.0))).collect();
+                (0..n).map(|_| spin(rng, &weights[..])).collect()
+            }
+            Selection::Rank => {
+                let weights: Vec<f64> = (0..size).map(|i| (size - i) as f64).collect();
+                (0..n).map(|_| spin(rng, &weights[..])).collect()
+            }
+        }
+    }
+}
+
+impl Replacement {
+    /// `replacement`, either "plus" (the default) or "comma".
+    pub fn from_config(config: &Config, vars: &Section) -> Result<Replacement, ConfigErrors> {
+        let name = try!(config.get_default(vars, "replacement", "plus".to_string()));
+        match &name[..] {
+            "plus" => Ok(Replacement::Plus),
+            "comma" => Ok(Replacement::Comma),
+            _ => {
+                let why = format!("unknown replacement `{}` (expected plus or comma).", name);
+                Err(ConfigError::invalid(vars.key("replacement"), why).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use util::util::{derive_rng};
+    use super::{Selection};
+
+    fn counts(selection: &Selection, fitnesses: &[f64], mu: usize, n: usize) -> Vec<usize> {
+        let mut rng = derive_rng(1, &[]);
+        let mut counts = vec![0; fitnesses.len()];
+        for index in selection.select(&mut rng, fitnesses, mu, n).into_iter() {
+            counts[index] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn picks_n_valid_indices() {
+        let fitnesses = [0.0, 1.0, 2.0, 3.0, 4.0];
+        for selection in [Selection::Truncation, Selection::Tournament(3), Selection::Roulette, Selection::Rank].iter() {
+            let mut rng = derive_rng(2, &[]);
+            let picks = selection.select(&mut rng, &fitnesses, 3, 50);
+            assert_eq!(picks.len(), 50);
+            assert!(picks.iter().all(|&index| index < fitnesses.len()));
+        }
+    }
+
+    #[test]
+    fn truncation_only_picks_elites() {
+        let counts = counts(&Selection::Truncation, &[0.0, 1.0, 2.0, 3.0, 4.0], 2, 100);
+        assert_eq!(counts[2..].iter().fold(0, |accum, &c| accum + c), 0);
+        assert!(counts[0] > 0 && counts[1] > 0);
+    }
+
+    #[test]
+    fn better_is_likelier() {
+        let fitnesses = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        for selection in [Selection::Tournament(3), Selection::Roulette, Selection::Rank].iter() {
+            let counts = counts(selection, &fitnesses, 8, 4000);
+            assert!(counts[0] > counts[7], "{:?}: {:?}", selection, counts);
+        }
+    }
+
+    #[test]
+    fn a_tournament_of_one_is_uniform() {
+        let counts = counts(&Selection::Tournament(1), &[0.0, 1.0, 2.0, 3.0], 4, 4000);
+        assert!(counts.iter().all(|&c| c > 800 && c < 1200), "{:?}", counts);
+    }
+}
@@ -0,0 +1,179 @@
+use std::f64;
+use std::collections::{HashSet};
+use rustc_serialize::{Encodable, Encoder, Decodable, Decoder};
+
+use chapters::chapter2::dungeon::{Dungeon};
+
+/// how an individual fared. the phenotype is transient: it is not
+/// encoded (in checkpoints or winner files), and decodes as `None`.
+#[derive(Clone, Debug)]
+pub struct Statistic {
+    pub iteration: u32,
+    pub fitness: f64,
+    pub scores: Vec<f64>, // one per evaluation, unweighted
+    pub descriptors: Vec<f64>, // behaviour descriptors, if any are used
+    pub novelty: f64, // for novelty search, otherwise 0.0
+    pub ranking: f64, // what the population is sorted on (lower is better)
+    pub mutation: f64, // the mutation strength it was made with
+    pub fingerprint: u64, // of its dungeon (see `Dungeon::fingerprint`)
+    pub phenotype: Option<Dungeon>, // the dungeon it was evaluated on
+}
+
+impl Statistic {
+    pub fn new(iteration: u32, fitness: f64, scores: Vec<f64>) -> Statistic {
+        Statistic {
+            iteration: iteration,
+            fitness: fitness,
+            scores: scores,
+            descriptors: vec![],
+            novelty: 0.0,
+            ranking: fitness,
+            mutation: 0.0,
+            fingerprint: 0,
+            phenotype: None,
+        }
+    }
+
+    pub fn empty() -> Statistic {
+        Statistic {
+            iteration: 0,
+            fitness: -1.0,
+            scores: vec![],
+            descriptors: vec![],
+            novelty: 0.0,
+            ranking: -1.0,
+            mutation: 0.0,
+            fingerprint: 0,
+            phenotype: None,
+        }
+    }
+}
+
+impl Encodable for Statistic {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        s.emit_struct("Statistic", 8, |s| {
+            try!(s.emit_struct_field("iteration", 0, |s| self.iteration.encode(s)));
+            try!(s.emit_struct_field("fitness", 1, |s| self.fitness.encode(s)));
+            try!(s.emit_struct_field("scores", 2, |s| self.scores.encode(s)));
+            try!(s.emit_struct_field("descriptors", 3, |s| self.descriptors.encode(s)));
+            try!(s.emit_struct_field("novelty", 4, |s| self.novelty.encode(s)));
+            try!(s.emit_struct_field("ranking", 5, |s| self.ranking.encode(s)));
+            try!(s.emit_struct_field("mutation", 6, |s| self.mutation.encode(s)));
+            try!(s.emit_struct_field("fingerprint", 7, |s| self.fingerprint.encode(s)));
+            Ok(())
+        })
+    }
+}
+
+impl Decodable for Statistic {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Statistic, D::Error> {
+        d.read_struct("Statistic", 8, |d| {
+            Ok(Statistic {
+                iteration: try!(d.read_struct_field("iteration", 0, Decodable::decode)),
+                fitness: try!(d.read_struct_field("fitness", 1, Decodable::decode)),
+                scores: try!(d.read_struct_field("scores", 2, Decodable::decode)),
+                descriptors: try!(d.read_struct_field("descriptors", 3, Decodable::decode)),
+                novelty: try!(d.read_struct_field("novelty", 4, Decodable::decode)),
+                ranking: try!(d.read_struct_field("ranking", 5, Decodable::decode)),
+                mutation: try!(d.read_struct_field("mutation", 6, Decodable::decode)),
+                fingerprint: try!(d.read_struct_field("fingerprint", 7, Decodable::decode)),
+                phenotype: None,
+            })
+        })
+    }
+}
+
+/// a summary of one generation, for comparing runs. `throughput`
+/// decodes as 0.0 when missing, as in checkpoints saved before it.
+#[derive(Clone, Debug, RustcEncodable)]
+pub struct Generation {
+    pub iteration: u32,
+    pub min: f64, // fitness
+    pub mean: f64,
+    pub max: f64,
+    pub stddev: f64,
+    pub scores: Vec<f64>, // the mean of every evaluation
+    pub diversity: f64, // the fraction of distinct dungeons
+    pub mutation: f64, // the mutation strength the generation was made with
+    pub seconds: f64, // wall-clock time since the run started
+    pub throughput: f64, // dungeons evaluated per second of evaluating
+}
+
+impl Decodable for Generation {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Generation, D::Error> {
+        d.read_struct("Generation", 10, |d| {
+            let throughput: Option<f64> = try!(d.read_struct_field("throughput", 9, Decodable::decode));
+            Ok(Generation {
+                iteration: try!(d.read_struct_field("iteration", 0, Decodable::decode)),
+                min: try!(d.read_struct_field("min", 1, Decodable::decode)),
+                mean: try!(d.read_struct_field("mean", 2, Decodable::decode)),
+                max: try!(d.read_struct_field("max", 3, Decodable::decode)),
+                stddev: try!(d.read_struct_field("stddev", 4, Decodable::decode)),
+                scores: try!(d.read_struct_field("scores", 5, Decodable::decode)),
+                diversity: try!(d.read_struct_field("diversity", 6, Decodable::decode)),
+                mutation: try!(d.read_struct_field("mutation", 7, Decodable::decode)),
+                seconds: try!(d.read_struct_field("seconds", 8, Decodable::decode)),
+                throughput: throughput.unwrap_or(0.0),
+            })
+        })
+    }
+}
+
+impl Generation {
+    pub fn new(iteration: u32, population: &[&Statistic], mutation: f64, seconds: f64, throughput: f64) -> Generation {
+        let n = population.len().max(1) as f64;
+        let fitnesses: Vec<f64> = population.iter().map(|s| s.fitness).collect();
+        let min = fitnesses.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = fitnesses.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mean = fitnesses.iter().fold(0.0, |accum, f| accum + f) / n;
+        let variance = fitnesses.iter().fold(0.0, |accum, f| accum + (f - mean).powi(2)) / n;
+        let evaluations = population.first().map(|s| s.scores.len()).unwrap_or(0);
+        let scores = (0..evaluations).map(|i| {
+            population.iter().fold(0.0, |accum, s| accum + s.scores[i]) / n
+        }).collect();
+        let distinct: HashSet<u64> = population.iter().map(|s| s.fingerprint).collect();
+        Generation {
+            iteration: iteration,
+            min: min,
+            mean: mean,
+            max: max,
+            stddev: variance.sqrt(),
+            scores: scores,
+            diversity: distinct.len() as f64 / n,
+            mutation: mutation,
+            seconds: seconds,
+            throughput: throughput,
+        }
+    }
+
+    /// a one-line summary, for following a run as it goes.
+    pub fn progress(&self, iterations: u32) -> String {
+        format!("iteration {}/{}: best {:.2}, mean {:.2} (sd {:.2}), diversity {:.2}, mutation {:.3}, {:.1}s, {:.0} evaluations/s",
+                self.iteration + 1, iterations, self.min, self.mean, self.stddev, self.diversity, self.mutation, self.seconds, self.throughput)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_serialize::json;
+    use chapters::chapter2::dungeon::{Dungeon};
+    use super::{Statistic, Generation};
+
+    #[test]
+    fn phenotypes_are_not_encoded() {
+        let mut statistic = Statistic::new(3, 1.5, vec![0.5, 1.0]);
+        statistic.phenotype = Some(Dungeon::new(4, 4, None));
+        let encoded = json::encode(&statistic).unwrap();
+        assert!(!encoded.contains("phenotype"));
+        let decoded: Statistic = json::decode(&encoded[..]).unwrap();
+        assert_eq!((decoded.iteration, decoded.fitness, decoded.scores), (3, 1.5, vec![0.5, 1.0]));
+        assert!(decoded.phenotype.is_none());
+    }
+
+    #[test]
+    fn throughput_defaults_to_zero() {
+        let old = r#"{"iteration":2,"min":1.0,"mean":2.0,"max":3.0,"stddev":0.5,"scores":[1.0],"diversity":1.0,"mutation":0.1,"seconds":4.0}"#;
+        let generation: Generation = json::decode(old).unwrap();
+        assert_eq!((generation.iteration, generation.seconds, generation.throughput), (2, 4.0, 0.0));
+    }
+}
@@ -0,0 +1,185 @@
+use std::f64;
+use std::fmt;
+
+use chapters::chapter2::statistics::{Generation};
+use util::config::{Config, ConfigError, ConfigErrors, Section};
+
+/// why a run ended.
+#[derive(Clone, Debug, PartialEq, RustcEncodable)]
+pub enum StopReason {
+    Iterations,     // it ran every iteration
+    TargetFitness,  // the best fitness reached `target_fitness`
+    Stagnation,     // the best fitness so far did not improve for `stagnation` iterations
+    TimeBudget,     // it ran for `time_budget` seconds
+    MaxEvaluations, // it evaluated `max_evaluations` dungeons
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match *self {
+            StopReason::Iterations => "ran every iteration",
+            StopReason::TargetFitness => "reached the target fitness",
+            StopReason::Stagnation => "stagnated",
+            StopReason::TimeBudget => "ran out of time",
+            StopReason::MaxEvaluations => "ran out of evaluations",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+/// conditions that end a run before its last iteration. any that is
+/// not configured never applies. they are checked after every
+/// generation, so `max_evaluations` can be overshot by up to one
+/// generation's worth (lambda) of evaluations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StopCriteria {
+    target_fitness: Option<f64>,
+    stagnation: Option<usize>,
+    time_budget: Option<f64>, // in seconds
+    max_evaluations: Option<usize>,
+}
+
+impl StopCriteria {
+    /// `target_fitness`, `stagnation`, `time_budget` and
+    /// `max_evaluations`, all optional.
+    pub fn from_config(config: &Config, vars: &Section) -> Result<StopCriteria, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        let target_fitness = if vars.contains("target_fitness") {
+            errors.check(config.get_float(vars, "target_fitness")).map(Some)
+        } else {
+            Some(None)
+        };
+        let stagnation = errors.check(config.get_default(vars, "stagnation", 0));
+        let time_budget = errors.check(config.get_default(vars, "time_budget", 0.0));
+        let max_evaluations = errors.check(config.get_default(vars, "max_evaluations", 0));
+        if let Some(budget) = time_budget {
+            if budget < 0.0 {
+                let why = format!("must not be negative, not {}.", budget);
+                errors.push(ConfigError::invalid(vars.key("time_budget"), why));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        // zero turns a criterion off.
+        let positive = |n: usize| if n > 0 { Some(n) } else { None };
+        Ok(StopCriteria {
+            target_fitness: target_fitness.unwrap(),
+            stagnation: positive(stagnation.unwrap()),
+            time_budget: time_budget.and_then(|t| if t > 0.0 { Some(t) } else { None }),
+            max_evaluations: positive(max_evaluations.unwrap()),
+        })
+    }
+
+    /// no criteria: run every iteration.
+    pub fn none() -> StopCriteria {
+        StopCriteria {
+            target_fitness: None,
+            stagnation: None,
+            time_budget: None,
+            max_evaluations: None,
+        }
+    }
+
+    /// whether to stop after the last generation of `history`, having
+    /// evaluated `evaluations` dungeons so far.
+    pub fn check(&self, history: &[Generation], evaluations: usize) -> Option<StopReason> {
+        let last = match history.last() {
+            Some(last) => last,
+            None => return None
+        };
+        if let Some(target) = self.target_fitness {
+            if last.min <= target {
+                return Some(StopReason::TargetFitness);
+            }
+        }
+        if let Some(n) = self.stagnation {
+            // nothing in the last n iterations beat the best before them.
+            // the best so far rather than each generation's, as the
+            // population's best can get worse (with comma replacement).
+            if history.len() > n {
+                let best = |generations: &[Generation]| generations.iter().map(|g| g.min).fold(f64::INFINITY, f64::min);
+                let (before, since) = history.split_at(history.len() - n);
+                if best(since) >= best(before) {
+                    return Some(StopReason::Stagnation);
+                }
+            }
+        }
+        if let Some(budget) = self.time_budget {
+            if last.seconds >= budget {
+                return Some(StopReason::TimeBudget);
+            }
+        }
+        if let Some(max) = self.max_evaluations {
+            if evaluations >= max {
+                return Some(StopReason::MaxEvaluations);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chapters::chapter2::statistics::{Generation};
+    use super::{StopCriteria, StopReason};
+
+    fn generation(iteration: u32, min: f64) -> Generation {
+        Generation {
+            iteration: iteration,
+            min: min,
+            mean: min,
+            max: min,
+            stddev: 0.0,
+            scores: vec![],
+            diversity: 1.0,
+            mutation: 0.1,
+            seconds: iteration as f64,
+            throughput: 0.0,
+        }
+    }
+
+    fn history(mins: &[f64]) -> Vec<Generation> {
+        mins.iter().enumerate().map(|(i, &min)| generation(i as u32, min)).collect()
+    }
+
+    #[test]
+    fn no_criteria_never_stop() {
+        assert_eq!(StopCriteria::none().check(&history(&[5.0, 5.0, 5.0, 5.0])[..], 1000000), None);
+        assert_eq!(StopCriteria::none().check(&[], 0), None);
+    }
+
+    #[test]
+    fn target_fitness() {
+        let criteria = StopCriteria { target_fitness: Some(1.0), ..StopCriteria::none() };
+        assert_eq!(criteria.check(&history(&[3.0, 2.0])[..], 0), None);
+        assert_eq!(criteria.check(&history(&[3.0, 1.0])[..], 0), Some(StopReason::TargetFitness));
+    }
+
+    #[test]
+    fn stagnation_needs_n_iterations_without_improvement() {
+        let criteria = StopCriteria { stagnation: Some(2), ..StopCriteria::none() };
+        assert_eq!(criteria.check(&history(&[3.0, 3.0])[..], 0), None);
+        assert_eq!(criteria.check(&history(&[3.0, 2.0, 2.0])[..], 0), None);
+        assert_eq!(criteria.check(&history(&[3.0, 2.0, 2.0, 2.0])[..], 0), Some(StopReason::Stagnation));
+    }
+
+    #[test]
+    fn stagnation_compares_with_the_best_so_far() {
+        let criteria = StopCriteria { stagnation: Some(2), ..StopCriteria::none() };
+        // improved, then got worse: no better than the best two
+        // iterations ago, even though better than that generation.
+        assert_eq!(criteria.check(&history(&[5.0, 1.0, 4.0, 3.0])[..], 0), Some(StopReason::Stagnation));
+        assert_eq!(criteria.check(&history(&[5.0, 1.0, 4.0, 0.5])[..], 0), None);
+    }
+
+    #[test]
+    fn budgets() {
+        let criteria = StopCriteria { time_budget: Some(2.0), ..StopCriteria::none() };
+        assert_eq!(criteria.check(&history(&[1.0, 1.0])[..], 0), None);
+        assert_eq!(criteria.check(&history(&[1.0, 1.0, 1.0])[..], 0), Some(StopReason::TimeBudget));
+        let criteria = StopCriteria { max_evaluations: Some(100), ..StopCriteria::none() };
+        assert_eq!(criteria.check(&history(&[1.0])[..], 99), None);
+        assert_eq!(criteria.check(&history(&[1.0])[..], 120), Some(StopReason::MaxEvaluations));
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+use std::io::{Write};
+use std::fs::{File, copy};
+use std::collections::{HashMap};
+use image::{self, GenericImage};
+
+use chapters::chapter2::celloption::{CellOption};
+use chapters::chapter2::dungeon::{Dungeon};
+use util::config::{SpriteConfig};
+use util::spritesheet::{SpriteSheet};
+
+/// exports dungeons as Tiled maps (TMX), along with a tileset (TSX)
+/// derived from the spritesheet configuration.
+pub struct TiledExport {
+    name: String,
+    image_path: PathBuf,
+    image_width: u32,
+    image_height: u32,
+    tile_width: u32,
+    tile_height: u32,
+    columns: u32,
+    frame_duration: u32,      // in milliseconds
+    ids: HashMap<String, Vec<u32>>, // tile ids per sprite (several if animated)
+}
+
+// attribute values must not break out of their quotes.
+fn escape(value: &str) -> String {
+    value.replace("&", "&amp;").replace("\"", "&quot;").replace("<", "&lt;").replace(">", "&gt;")
+}
+
+fn create(path: &Path) -> File {
+    match File::create(path) {
+        Ok(f) => f,
+        Err(why) => panic!("Could not create {}: {}", path.display(), why)
+    }
+}
+
+fn write_all(path: &Path, contents: &str) {
+    let mut file = create(path);
+    match file.write_all(contents.as_bytes()) {
+        Err(why) => panic!("Could not write {}: {}", path.display(), why),
+        _ => ()
+    }
+}
+
+impl TiledExport {
+    pub fn new(spritesheet_path: &Path, frame_duration: u32) -> TiledExport {
+        let sheet = match image::open(spritesheet_path) {
+            Ok(i) => i,
+            Err(why) => panic!("Could not load spritesheet {}: {:?}", spritesheet_path.display(), why)
+        };
+        let (image_width, image_height) = sheet.dimensions();
+        let toml_path = SpriteSheet::location(spritesheet_path).expect("No spritesheet configuration file.");
+        let (tile_width, tile_height) = SpriteConfig::tile_size(&toml_path);
+        let (tile_width, tile_height) = (tile_width as u32, tile_height as u32);
+        let columns = image_width / tile_width;
+        // only sprites on the spritesheet grid can be part of the tileset.
+        let mut ids = HashMap::new();
+        for (name, rects) in SpriteConfig::process_spritesheet(&toml_path) {
+            let frames: Vec<u32> = rects.iter().filter_map(|rect| {
+                let (x, y, w, h) = rect.source();
+                if w == tile_width && h == tile_height {
+                    Some((y / tile_height) * columns + x / tile_width)
+                } else {
+                    None
+                }
+            }).collect();
+            if !frames.is_empty() {
+                ids.insert(name, frames);
+            }
+        }
+        let name = spritesheet_path.file_stem().and_then(|s| s.to_str()).unwrap_or("tileset").to_string();
+        TiledExport {
+            name: name,
+            image_path: PathBuf::from(spritesheet_path),
+            image_width: image_width,
+            image_height: image_height,
+            tile_width: tile_width,
+            tile_height: tile_height,
+            columns: columns,
+            frame_duration: frame_duration,
+            ids: ids,
+        }
+    }
+
+    fn tileset_file(&self) -> String {
+        format!("{}.tsx", self.name)
+    }
+
+    fn image_file(&self) -> String {
+        self.image_path.file_name().and_then(|s| s.to_str()).unwrap().to_string()
+    }
+
+    // global tile id of the (first frame of the) named sprite.
+    fn gid(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).map(|frames| frames[0] + 1)
+    }
+
+    /// write the TSX tileset into `out`, with a copy of the
+    /// spritesheet image next to it.
+    pub fn write_tileset(&self, out: &Path) {
+        let image_copy = out.join(self.image_file());
+        match copy(&self.image_path, &image_copy) {
+            Err(why) => panic!("Could not copy {} to {}: {}", self.image_path.display(), image_copy.display(), why),
+            _ => ()
+        }
+        let rows = self.image_height / self.tile_height;
+        let mut tsx = String::new();
+        tsx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        tsx.push_str(&format!("<tileset name=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" tilecount=\"{}\" columns=\"{}\">\n",
+                              escape(&self.name[..]), self.tile_width, self.tile_height, rows * self.columns, self.columns)[..]);
+        tsx.push_str(&format!(" <image source=\"{}\" width=\"{}\" height=\"{}\"/>\n",
+                              escape(&self.image_file()[..]), self.image_width, self.image_height)[..]);
+        let mut names: Vec<&String> = self.ids.keys().collect();
+        names.sort();
+        for name in names {
+            let ref frames = self.ids[name];
+            tsx.push_str(&format!(" <tile id=\"{}\">\n", frames[0])[..]);
+            tsx.push_str(&format!("  <properties>\n   <property name=\"name\" value=\"{}\"/>\n  </properties>\n", escape(&name[..]))[..]);
+            if frames.len() > 1 {
+                tsx.push_str("  <animation>\n");
+                for frame in frames.iter() {
+                    tsx.push_str(&format!("   <frame tileid=\"{}\" duration=\"{}\"/>\n", frame, self.frame_duration)[..]);
+                }
+                tsx.push_str("  </animation>\n");
+            }
+            tsx.push_str(" </tile>\n");
+        }
+        tsx.push_str("</tileset>\n");
+        write_all(&out.join(self.tileset_file()), &tsx[..]);
+    }
+
+    // a tile object; tiled anchors these at their bottom-left corner.
+    fn object(&self, id: u32, kind: &str, name: &str, x: u32, y: u32) -> String {
+        let px = x * self.tile_width;
+        let py = (y + 1) * self.tile_height;
+        let gid = match self.gid(name) {
+            Some(gid) => format!(" gid=\"{}\"", gid),
+            None => String::new()
+        };
+        format!("  <object id=\"{}\" name=\"{}\" type=\"{}\"{} x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\n",
+                id, escape(name), kind, gid, px, py, self.tile_width, self.tile_height)
+    }
+
+    /// write a dungeon as a TMX map referring to the tileset written
+    /// by `write_tileset`. tiles go in a tile layer, occupants and
+    /// items in object layers.
+    pub fn write_map(&self, path: &Path, dungeon: &Dungeon) {
+        let w = dungeon.width as u32;
+        let h = dungeon.height as u32;
+        let mut data: Vec<String> = vec![];
+        let mut occupants = String::new();
+        let mut items = String::new();
+        let mut object_id = 1;
+        for y in 0..h {
+            let row: Vec<String> = (0..w).map(|x| {
+                let cell = dungeon.get_cell(x, y);
+                let gid = cell.tile.as_ref().and_then(|t| self.gid(&t.name()[..])).unwrap_or(0);
+                gid.to_string()
+            }).collect();
+            data.push(row.join(","));
+            for x in 0..w {
+                let cell = dungeon.get_cell(x, y);
+                if let Some(ref occupant) = cell.occupant {
+                    occupants.push_str(&self.object(object_id, "occupant", &occupant.name()[..], x, y)[..]);
+                    object_id += 1;
+                }
+                for item in cell.items.iter() {
+                    items.push_str(&self.object(object_id, "item", &item.name()[..], x, y)[..]);
+                    object_id += 1;
+                }
+            }
+        }
+        let mut tmx = String::new();
+        tmx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        tmx.push_str(&format!("<map version=\"1.0\" orientation=\"orthogonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" nextobjectid=\"{}\">\n",
+                              w, h, self.tile_width, self.tile_height, object_id)[..]);
+        tmx.push_str(&format!(" <tileset firstgid=\"1\" source=\"{}\"/>\n", escape(&self.tileset_file()[..]))[..]);
+        tmx.push_str(&format!(" <layer name=\"tiles\" width=\"{}\" height=\"{}\">\n  <data encoding=\"csv\">\n", w, h)[..]);
+        tmx.push_str(&data.join(",\n")[..]);
+        tmx.push_str("\n</data>\n </layer>\n");
+        tmx.push_str(&format!(" <objectgroup name=\"occupants\">\n{} </objectgroup>\n", occupants)[..]);
+        tmx.push_str(&format!(" <objectgroup name=\"items\">\n{} </objectgroup>\n", items)[..]);
+        tmx.push_str("</map>\n");
+        write_all(path, &tmx[..]);
+    }
+}
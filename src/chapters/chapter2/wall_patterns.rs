@@ -0,0 +1,198 @@
+use chapters::chapter2::dungeon::{Dungeon};
+use chapters::chapter2::celloption::{Tile, Occupant};
+use chapters::chapter2::genotype::{Genotype, to_json, from_json};
+use chapters::chapter2::crossover::{Crossover};
+use chapters::chapter2::phenotype::{Seed};
+use util::config::{Config, ConfigError, ConfigErrors};
+
+use std::collections::{HashMap};
+use rand::{Rng};
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct WallPatterns {
+    seed: Seed,
+    patterns: Vec<Pattern>,
+    pattern_width: u32,
+    pattern_height: u32,
+    indices: Vec<usize>,
+    occupants: Vec<(Occupant, (u32, u32))>,
+}
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+struct Pattern {
+    pattern: Vec<Option<Tile>>
+}
+
+impl Pattern {
+    fn from_config(mapping: &HashMap<char, &Tile>, key: String, description: Vec<String>, width: u32, height: u32) -> Result<Pattern, ConfigErrors> {
+        let mut errors = ConfigErrors::new();
+        if description.len() != height as usize {
+            let why = format!("has {} rows, expected {}.", description.len(), height);
+            errors.push(ConfigError::invalid(key.clone(), why));
+        }
+        for (row, line) in description.iter().enumerate() {
+            let n = line.chars().count();
+            if n != width as usize {
+                let why = format!("is {} characters wide, expected {}.", n, width);
+                errors.push(ConfigError::invalid(format!("{}[{}]", key, row), why));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let size = width as usize * height as usize;
+        let mut pattern = Vec::with_capacity(size);
+        for line in description {
+            for ch in line.chars() {
+                let tile = mapping.get(&(ch));
+                match tile {
+                    None => pattern.push(None),
+                    Some(&tile) => pattern.push(Some((*tile).clone()))
+                }
+            }
+        }
+        Ok(Pattern {
+            pattern: pattern
+        })
+    }
+}
+
+impl WallPatterns {
+    pub fn new(config: &Config, seed: &Seed) -> Result<WallPatterns, ConfigErrors> {
+        // read in the configurations
+        let mut errors = ConfigErrors::new();
+        let wallpatterns = try!(config.get_table(None, "wallpatterns"));
+        let tile_vars = try!(config.get_table(Some(&wallpatterns), "tiles"));
+        let pattern_width = errors.check(config.get_integer(&tile_vars, "width"));
+        let pattern_height = errors.check(config.get_integer(&tile_vars, "height"));
+        for &(key, size) in [("width", pattern_width), ("height", pattern_height)].iter() {
+            if let Some(size) = size {
+                if size < 1 {
+                    errors.push(ConfigError::invalid(tile_vars.key(key), "must be at least 1.".to_string()));
+                }
+            }
+        }
+        let mut mapping = HashMap::new();
+        let tiles = config.get_listing(&tile_vars, vec!["width", "height"]);
+        for tile in tiles {
+            let name = &tile[..];
+            match (seed.tiles.get(name), errors.check(config.get_char(&tile_vars, name))) {
+                (Some(graphical_tile), Some(ch)) => {
+                    mapping.insert(ch, graphical_tile);
+                }
+                (None, _) => errors.push(ConfigError::invalid(tile_vars.key(name), "is not a known tile.".to_string())),
+                _ => ()
+            }
+        }
+        let room_vars = try!(config.get_table(Some(&wallpatterns), "rooms"));
+        let rooms = config.get_listing(&room_vars, vec![]);
+        if rooms.is_empty() {
+            errors.push(ConfigError::invalid(room_vars.path().to_string(), "needs at least one pattern.".to_string()));
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let (pattern_width, pattern_height) = (pattern_width.unwrap() as u32, pattern_height.unwrap() as u32);
+        let mut patterns = vec![];
+        for r in rooms.iter() {
+            let description = errors.check(config.get_array(&room_vars, r));
+            if let Some(description) = description {
+                let pattern = Pattern::from_config(&mapping, room_vars.key(r), description, pattern_width, pattern_height);
+                if let Some(pattern) = errors.merge(pattern) {
+                    patterns.push(pattern);
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(WallPatterns {
+            seed: seed.clone(),
+            patterns: patterns,
+            pattern_width: pattern_width,
+            pattern_height: pattern_height,
+            indices: vec![],
+            occupants: vec![],
+        })
+    }
+
+}
+
+impl Genotype for WallPatterns {
+    fn initialize<T: Rng>(&self, rng: &mut T) -> WallPatterns {
+        let n = self.patterns.len();
+        assert!(n != 0);
+        let indices = rng.gen_iter::<usize>().take(n).map(|v| v % n).collect();
+        let occupants = self.seed.random_occupants(rng);
+        WallPatterns {
+            seed: self.seed.clone(),
+            patterns: self.patterns.clone(),
+            pattern_width: self.pattern_width,
+            pattern_height: self.pattern_height,
+            indices: indices,
+            occupants: occupants,
+        }
+    }
+
+    fn mutate<T: Rng>(&mut self, rng: &mut T, percentage: f64) {
+        let length = self.patterns.len();
+        let n = (length as f64 * percentage) as u32;
+        for _ in 0..n {
+            let index = rng.gen_range(0, length);
+            let val = rng.gen_range(0, length);
+            self.indices[index] = val;
+        }
+        self.occupants = self.seed.random_occupants(rng);
+    }
+
+    fn crossover<T: Rng>(&self, rng: &mut T, other: &WallPatterns, scheme: &Crossover) -> WallPatterns {
+        let mut child = self.clone();
+        child.indices = scheme.recombine(rng, &self.indices[..], &other.indices[..]);
+        child
+    }
+
+    fn encode_json(&self) -> String {
+        to_json(self)
+    }
+
+    fn decode_json(&self, json: &str) -> Result<WallPatterns, String> {
+        from_json(json)
+    }
+
+    fn encode_genome(&self) -> String {
+        to_json(&(&self.indices, &self.occupants))
+    }
+
+    fn generate(&self) -> Dungeon {
+        // draw the patterns according to the indices we have.
+        let w = self.seed.width;
+        let h = self.seed.height;
+        let n = self.patterns.len();
+        let mut dungeon = Dungeon::new(w, h, None);
+        for i in 0..w {
+            let x: u32 = i / self.pattern_width;
+            let inner_x = i % self.pattern_width;
+            for j in 0..h {
+                let y: u32 = j / self.pattern_height;
+                // NB: patterns will show up when # of patterns is not enough to tile the whole area
+                let index = self.indices[(y * self.pattern_width + x) as usize % n];
+                let ref pattern = self.patterns[index];
+                // here, we have to invert due to a mismatch between how we draw the patterns and opengl coords
+                let inner_y = self.pattern_height - (j % self.pattern_height) - 1;
+                let tile_index = inner_y * self.pattern_width + inner_x;
+                if let Some(tile) = pattern.pattern[tile_index as usize].clone() {
+                    dungeon.set_tile(i, j, &tile);
+                }
+            }
+        }
+        // draw the occupants if their tile is not otherwise occupied.
+        for (occupant, coord) in self.occupants.clone() {
+            let x = coord.0;
+            let y = coord.1;
+            if dungeon.has_attribute(x, y, "floor") {
+                dungeon.set_occupant(x, y, &occupant);
+            }
+        }
+        dungeon.clone()
+    }
+}
@@ -0,0 +1,30 @@
+use freetype::{Face};
+use opengl_graphics::{GlGraphics};
+use input::{Event, RenderEvent};
+
+use util::text::{render_text};
+use util::config::{Config};
+use util::chapter::{Chapter, ChapterCallback};
+
+pub fn chapter3_entry(config: &Config) -> ChapterCallback {
+
+    box move |gl: &mut GlGraphics, face: &mut Face, e: Event| {
+
+        if let Some(args) = e.render_args() {
+            let info = format!("chapter3 stub");
+            render_text(face, gl, args.viewport(), 10.0, 410.0, &info[..]);
+        };
+    }
+}
+
+pub struct Chapter3;
+
+impl Chapter for Chapter3 {
+    fn name(&self) -> &'static str { "chapter3" }
+
+    fn description(&self) -> &'static str { "Constructive generation (stub)." }
+
+    fn interactive(&self, config: &Config) -> Result<ChapterCallback, String> {
+        Ok(chapter3_entry(config))
+    }
+}
@@ -0,0 +1,20 @@
+pub mod chapter2;
+pub mod chapter3;
+
+use util::chapter::{Chapter};
+
+use chapters::chapter2::entry::{Chapter2};
+use chapters::chapter2::registry::{Registry};
+use chapters::chapter3::entry::{Chapter3};
+
+/// every available chapter, in book order.
+pub fn registry() -> Vec<Box<Chapter>> {
+    with_registry(Registry::with_defaults())
+}
+
+/// every available chapter, with chapter 2 using `registry`'s
+/// genotypes and evaluations. start from `Registry::with_defaults()`
+/// to add to the built-in ones, and pass the result to `cli::run`.
+pub fn with_registry(registry: Registry) -> Vec<Box<Chapter>> {
+    vec![box Chapter2::with_registry(registry), box Chapter3]
+}
@@ -0,0 +1,182 @@
+use opengl_graphics::{GlGraphics};
+use sdl2_window::{Sdl2Window};
+use toml::{Value};
+use window::{WindowSettings, Size};
+use event_loop::{Events};
+use docopt::{Docopt};
+use graphics::{color};
+
+use std::path::{Path, PathBuf};
+use std::fmt::{Display};
+use std::io::{Write, stderr};
+use std::process::{exit};
+
+use util::config::{Config};
+use util::chapter::{Chapter, GenerateOptions};
+use util::sweep::{Axis, sweep};
+
+static USAGE: &'static str = "
+Usage: pcg list
+       pcg check-config <chapter> [--config=<file>] [--set=<kv>...]
+       pcg <chapter> [--config=<file>] [--seed=<n>] [--set=<kv>...] [--resume=<file>]
+       pcg generate <chapter> --headless [--count=<n>] [--out=<dir>] [--config=<file>] [--seed=<n>] [--set=<kv>...] [--thumbnails=<format>] [--tmx] [--resume=<file>]
+       pcg evaluate <chapter> <map>... [--config=<file>] [--set=<kv>...]
+       pcg sweep <chapter> --vary=<spec>... [--csv=<file>] [--config=<file>] [--seed=<n>] [--set=<kv>...]
+
+Options:
+    --config=<file>        Configuration file (defaults to config/<chapter>.toml,
+                           looked for from the working directory, the
+                           executable and the source tree).
+    --headless             Run without opening a window.
+    --count=<n>            Number of independent runs [default: 1].
+    --out=<dir>            Directory to write the winners to [default: out].
+    --seed=<n>             Seed for the run (overrides `[mu-lambda] seed`).
+    --set=<kv>             Override a configuration value, e.g. `mu-lambda.mu=50`.
+    --vary=<spec>          Sweep a key over a list (`mu-lambda.mu=10,20,50`) or
+                           a range (`mu-lambda.mutation=0.1..0.5:0.1`).
+    --csv=<file>           Where to write the sweep results [default: sweep.csv].
+    --thumbnails=<format>  Also render every winner to an image (png, jpeg or ppm).
+    --tmx                  Also export every winner as a Tiled map (with a TSX tileset).
+    --resume=<file>        Carry on from a checkpoint (sets `[mu-lambda] resume`).
+";
+
+#[derive(RustcDecodable, Debug)]
+struct Args {
+    cmd_list: bool,
+    cmd_check_config: bool,
+    cmd_generate: bool,
+    cmd_evaluate: bool,
+    cmd_sweep: bool,
+    arg_chapter: String,
+    arg_map: Vec<String>,
+    flag_config: Option<String>,
+    flag_headless: bool,
+    flag_count: usize,
+    flag_out: String,
+    flag_seed: Option<u64>,
+    flag_set: Vec<String>,
+    flag_vary: Vec<String>,
+    flag_csv: String,
+    flag_thumbnails: Option<String>,
+    flag_tmx: bool,
+    flag_resume: Option<String>,
+}
+
+/// report a problem and stop.
+fn exit_with<T: Display>(why: T) -> ! {
+    let _ = writeln!(&mut stderr(), "{}", why);
+    exit(1);
+}
+
+/// the command line, over the given chapters (usually
+/// `chapters::registry()`; see `chapters::with_registry` for adding
+/// genotypes and evaluations to chapter 2).
+pub fn run(chapters: Vec<Box<Chapter>>) {
+
+    let args: Args = Docopt::new(USAGE)
+        .and_then(|d| d.decode())
+        .unwrap_or_else(|e| e.exit());
+
+    if args.cmd_list {
+        for chapter in chapters.iter() {
+            println!("{:<12} {}", chapter.name(), chapter.description());
+        }
+        return;
+    }
+
+    let chapter = match chapters.into_iter().find(|chapter| chapter.name() == &args.arg_chapter[..]) {
+        Some(chapter) => chapter,
+        None => exit_with(format!("Could not find chapter {} (see `pcg list`).", args.arg_chapter))
+    };
+    let config_path = match args.flag_config {
+        Some(ref path) => PathBuf::from(path),
+        None => chapter.default_config()
+    };
+    let mut config = match Config::new(&config_path) {
+        Ok(config) => config,
+        Err(errors) => {
+            println!("{}: {} problem(s)", config_path.display(), errors.errors.len());
+            exit_with(errors)
+        }
+    };
+    if let Some(seed) = args.flag_seed {
+        config.set("mu-lambda.seed", Value::Integer(seed as i64)).unwrap_or_else(|why| exit_with(why));
+    }
+    if let Some(ref resume) = args.flag_resume {
+        config.set("mu-lambda.resume", Value::String(resume.clone())).unwrap_or_else(|why| exit_with(why));
+    }
+    for assignment in args.flag_set.iter() {
+        if let Err(why) = config.set_override(&assignment[..]) {
+            exit_with(why);
+        }
+    }
+
+    if args.cmd_check_config {
+        match chapter.check(&config) {
+            Ok(()) => println!("{}: ok", config_path.display()),
+            Err(errors) => {
+                println!("{}: {} problem(s)", config_path.display(), errors.errors.len());
+                exit_with(errors);
+            }
+        }
+        return;
+    }
+
+    if args.cmd_sweep {
+        let axes: Vec<Axis> = args.flag_vary.iter().map(|spec| {
+            Axis::parse(&spec[..]).unwrap_or_else(|why| exit_with(why))
+        }).collect();
+        if let Err(why) = sweep(&*chapter, &config, &axes[..], Path::new(&args.flag_csv[..])) {
+            exit_with(why);
+        }
+        return;
+    }
+
+    if args.cmd_generate && args.flag_headless {
+        let options = GenerateOptions {
+            count: args.flag_count,
+            out: PathBuf::from(&args.flag_out),
+            thumbnails: args.flag_thumbnails.clone(),
+            tmx: args.flag_tmx,
+        };
+        if let Err(why) = chapter.headless(&config, &options) {
+            exit_with(why);
+        }
+        return;
+    }
+
+    if args.cmd_evaluate {
+        if let Err(why) = chapter.evaluate(&config, &args.arg_map[..]) {
+            exit_with(why);
+        }
+        return;
+    }
+
+    let vars = config.get_table(None, "main").unwrap_or_else(|e| exit_with(e));
+    let window_width = config.get_default(&vars, "window_width", 800).unwrap_or_else(|e| exit_with(e));
+    let window_height = config.get_default(&vars, "window_height", 800).unwrap_or_else(|e| exit_with(e));
+    let font_name = config.get_string(&vars, "font").unwrap_or_else(|e| exit_with(e));
+    let font_size = config.get_default(&vars, "font_size", 14).unwrap_or_else(|e| exit_with(e));
+    let fps = config.get_default(&vars, "fps", 10).unwrap_or_else(|e| exit_with(e));
+
+    let opengl = shader_version::OpenGL::V3_2;
+    let size = Size { width: window_width, height: window_height };
+    let settings = WindowSettings::new("PCG", (window_width, window_height))
+        .exit_on_esc(true)
+        .opengl(opengl);
+        //.build()
+        //.unwrap();
+    let window = Sdl2Window::new(settings).unwrap();
+    let ref mut gl = GlGraphics::new(opengl);
+    let ft = freetype::Library::init().unwrap();
+    let font = Path::new(font_name);
+    let mut face = ft.new_face(&font, 0).unwrap();
+    face.set_pixel_sizes(0, font_size).unwrap();
+
+    let cb = chapter.interactive(&config).unwrap_or_else(|e| exit_with(e));
+    // TODO fps
+    for e in window.events() {
+        graphics::clear(color::BLACK, gl);
+        cb(gl, &mut face, e);
+    }
+}
@@ -0,0 +1,36 @@
+#![feature(step_by, convert, box_syntax, box_patterns, vec_push_all)]
+// #![allow(dead_code)]
+// #![allow(unused_variables)]
+// #![allow(unused_imports)]
+
+extern crate rand;
+extern crate toml;
+extern crate shader_version;
+extern crate input;
+extern crate event_loop;
+extern crate graphics;
+extern crate window;
+extern crate sdl2_window;
+extern crate opengl_graphics;
+extern crate freetype;
+extern crate threadpool;
+extern crate rustc_serialize;
+extern crate docopt;
+extern crate num_cpus;
+extern crate image;
+extern crate time;
+
+pub mod util {
+    pub mod util;
+    pub mod config;
+    pub mod sprite;
+    pub mod spritesheet;
+    pub mod text;
+    pub mod offscreen;
+    pub mod chapter;
+    pub mod sweep;
+}
+
+pub mod chapters;
+pub mod cli;
+
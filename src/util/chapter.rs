@@ -0,0 +1,76 @@
+use freetype::{Face};
+use opengl_graphics::{GlGraphics};
+use input::{Event};
+
+use std::env;
+use std::fs;
+use std::path::{PathBuf};
+
+use util::config::{Config, ConfigErrors};
+use util::sweep::{RunSummary};
+
+/// per-frame callback used by the interactive viewer.
+pub type ChapterCallback = Box<Fn(&mut GlGraphics, &mut Face, Event) -> ()>;
+
+/// what to write when running a chapter headless.
+pub struct GenerateOptions {
+    pub count: usize,
+    pub out: PathBuf,
+    pub thumbnails: Option<String>,
+    pub tmx: bool,
+}
+
+/// where default configuration files are looked for, in order: the
+/// working directory, the executable's directory and each one above
+/// it (so `target/release/pcg` finds the checkout's `config`), and
+/// the source tree the executable was built from.
+pub fn config_search_path() -> Vec<PathBuf> {
+    let mut directories = vec![];
+    if let Ok(directory) = env::current_dir() {
+        directories.push(directory);
+    }
+    if let Ok(executable) = env::current_exe() {
+        let mut directory = executable.parent();
+        while let Some(d) = directory {
+            directories.push(d.to_path_buf());
+            directory = d.parent();
+        }
+    }
+    directories.push(PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+    directories
+}
+
+/// a chapter of the book. chapters are listed in `chapters::registry`.
+pub trait Chapter {
+    /// the name used on the command line.
+    fn name(&self) -> &'static str;
+    /// a one-line summary, shown by `pcg list`.
+    fn description(&self) -> &'static str;
+    /// the configuration file used when `--config` is not given: the
+    /// first `config/<name>.toml` along `config_search_path()`.
+    fn default_config(&self) -> PathBuf {
+        let relative = PathBuf::from("config").join(format!("{}.toml", self.name()));
+        config_search_path().into_iter()
+            .map(|directory| directory.join(&relative))
+            .find(|path| fs::metadata(path).is_ok())
+            .unwrap_or(relative)
+    }
+    /// check the configuration, reporting every problem at once.
+    fn check(&self, _: &Config) -> Result<(), ConfigErrors> {
+        Ok(())
+    }
+    /// batch generation without a window.
+    fn headless(&self, _: &Config, _: &GenerateOptions) -> Result<(), String> {
+        Err(format!("{} has no headless mode.", self.name()))
+    }
+    /// a single headless run, summarised (used by `pcg sweep`).
+    fn summarize(&self, _: &Config) -> Result<RunSummary, String> {
+        Err(format!("{} cannot be swept.", self.name()))
+    }
+    /// score existing maps against the configured evaluations.
+    fn evaluate(&self, _: &Config, _: &[String]) -> Result<(), String> {
+        Err(format!("{} cannot evaluate maps.", self.name()))
+    }
+    /// set up the viewer; the callback is called for every event.
+    fn interactive(&self, config: &Config) -> Result<ChapterCallback, String>;
+}
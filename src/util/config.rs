@@ -2,82 +2,358 @@ use std::path::{Path, PathBuf};
 use std::io::{Read};
 use std::fs::{File};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use toml::{Parser, Value, Table, decode};
 use rustc_serialize::{Decodable};
 
 use util::sprite::{SpriteRect};
 
+/// what went wrong with a configuration value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigErrorKind {
+    Missing,
+    WrongType(&'static str), // the expected type
+    Invalid(String),
+}
+
+/// a problem with a single configuration value, along with the full
+/// dotted path of its key (e.g. `mu-lambda.evaluations`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    pub key: String,
+    pub kind: ConfigErrorKind,
+}
+
+impl ConfigError {
+    pub fn new(key: String, kind: ConfigErrorKind) -> ConfigError {
+        ConfigError {
+            key: key,
+            kind: kind
+        }
+    }
+
+    pub fn invalid(key: String, why: String) -> ConfigError {
+        ConfigError::new(key, ConfigErrorKind::Invalid(why))
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ConfigErrorKind::Missing => write!(f, "`{}` was not found.", self.key),
+            ConfigErrorKind::WrongType(expected) => write!(f, "`{}` is not {}.", self.key, expected),
+            ConfigErrorKind::Invalid(ref why) => write!(f, "`{}`: {}", self.key, why),
+        }
+    }
+}
+
+/// several configuration problems, so that they can all be reported
+/// at once instead of stopping at the first one.
+#[derive(Clone, Debug)]
+pub struct ConfigErrors {
+    pub errors: Vec<ConfigError>
+}
+
+impl ConfigErrors {
+    pub fn new() -> ConfigErrors {
+        ConfigErrors {
+            errors: vec![]
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// record an error; the same problem is only reported once.
+    pub fn push(&mut self, error: ConfigError) {
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+
+    /// keep the value, or record the error.
+    pub fn check<T>(&mut self, result: Result<T, ConfigError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    /// keep the value, or record all of its errors.
+    pub fn merge<T>(&mut self, result: Result<T, ConfigErrors>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(errors) => {
+                for error in errors.errors {
+                    self.push(error);
+                }
+                None
+            }
+        }
+    }
+
+    /// `Ok(())` if nothing went wrong.
+    pub fn into_result(self) -> Result<(), ConfigErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl From<ConfigError> for ConfigErrors {
+    fn from(error: ConfigError) -> ConfigErrors {
+        ConfigErrors {
+            errors: vec![error]
+        }
+    }
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for error in self.errors.iter() {
+            try!(write!(f, "{}\n", error));
+        }
+        Ok(())
+    }
+}
+
+// lay `over` on top of `base`, table by table.
+fn merge(base: &mut Table, over: &Table) {
+    for (key, value) in over.iter() {
+        let merged = match (base.get_mut(key), value) {
+            (Some(&mut Value::Table(ref mut inner)), &Value::Table(ref table)) => {
+                merge(inner, table);
+                true
+            }
+            _ => false
+        };
+        if !merged {
+            base.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+/// a TOML table, remembering where it lives in the configuration.
+#[derive(Clone, Debug)]
+pub struct Section<'a> {
+    path: String,
+    table: Option<&'a Table>, // none for a table that is not there
+}
+
+impl<'a> Section<'a> {
+    /// the dotted path of this table.
+    pub fn path(&self) -> &str {
+        &self.path[..]
+    }
+
+    /// the full dotted key of an entry in this table.
+    pub fn key(&self, name: &str) -> String {
+        if self.path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.path, name)
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&'a Value> {
+        self.table.and_then(|t| t.get(name))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    fn lookup(&self, name: &str) -> Result<&'a Value, ConfigError> {
+        self.get(name).ok_or(ConfigError::new(self.key(name), ConfigErrorKind::Missing))
+    }
+
+    fn wrong_type(&self, name: &str, expected: &'static str) -> ConfigError {
+        ConfigError::new(self.key(name), ConfigErrorKind::WrongType(expected))
+    }
+}
+
+#[derive(Clone)]
 pub struct Config {
     content: Table
 }
 
 impl Config {
-    pub fn new(config_path: &Path) -> Config {
-        let config_file = File::open(config_path);
-        let mut config = match config_file {
-            Ok(c) => c,
-            Err(why) => panic!("Could not open configuration file {}: {}", config_path.display(), why)
-        };
+    /// read and parse a configuration file. a file that cannot be read
+    /// is an error under its path; TOML syntax errors are reported
+    /// under `<path>:<line>:<column>`.
+    pub fn new(config_path: &Path) -> Result<Config, ConfigErrors> {
+        let name = config_path.display().to_string();
         let mut contents = String::new();
-        match config.read_to_string(&mut contents) {
-            Err(why) => panic!("Could not read configuration file {}: {}", config_path.display(), why),
-            _ => ()
+        if let Err(why) = File::open(config_path).and_then(|mut file| file.read_to_string(&mut contents)) {
+            return Err(ConfigError::invalid(name, format!("could not be read: {}", why)).into());
+        }
+        Config::parse(&name[..], &contents[..])
+    }
+
+    /// parse a configuration from TOML text; `name` (e.g. the file
+    /// path) prefixes the position of any syntax error.
+    pub fn parse(name: &str, contents: &str) -> Result<Config, ConfigErrors> {
+        let mut parser = Parser::new(contents);
+        match parser.parse() {
+            Some(value) => Ok(Config { content: value }),
+            None => {
+                let mut errors = ConfigErrors::new();
+                for error in parser.errors.iter() {
+                    let (line, column) = parser.to_linecol(error.lo);
+                    let key = format!("{}:{}:{}", name, line + 1, column + 1);
+                    errors.push(ConfigError::invalid(key, format!("{}.", error.desc)));
+                }
+                if errors.is_empty() {
+                    errors.push(ConfigError::invalid(name.to_string(), "is not valid TOML.".to_string()));
+                }
+                Err(errors)
+            }
+        }
+    }
+
+    /// set a value at a dotted key path (e.g. `mu-lambda.seed`),
+    /// creating any missing tables along the way. fails if a table on
+    /// the way is some other kind of value.
+    pub fn set(&mut self, key: &str, value: Value) -> Result<(), ConfigError> {
+        let parts: Vec<&str> = key.split('.').collect();
+        let (last, tables) = match parts.split_last() {
+            Some((last, tables)) if !last.is_empty() => (last, tables),
+            _ => return Err(ConfigError::invalid(key.to_string(), "is not a valid key.".to_string()))
         };
-        let value = Parser::new(&contents[..]).parse().expect("Configuration file is not valid TOML.");
-        Config {
-            content: value
+        let mut current = &mut self.content;
+        for (depth, part) in tables.iter().enumerate() {
+            let lookup = current;
+            let entry = lookup.entry(part.to_string()).or_insert(Value::Table(Table::new()));
+            current = match *entry {
+                Value::Table(ref mut table) => table,
+                _ => return Err(ConfigError::new(parts[..depth + 1].join("."), ConfigErrorKind::WrongType("a table")))
+            };
+        }
+        current.insert(last.to_string(), value);
+        Ok(())
+    }
+
+    /// a copy with every entry of `section` laid over the top level:
+    /// tables are merged, anything else is replaced.
+    pub fn overlay(&self, section: &Section) -> Config {
+        let mut config = self.clone();
+        if let Some(table) = section.table {
+            merge(&mut config.content, table);
+        }
+        config
+    }
+
+    /// apply a `key=value` override, e.g. `mu-lambda.mu=50`. the value
+    /// is read as TOML; anything that is not valid TOML is taken as a
+    /// plain string (so `mu-lambda.strategy=ListOfWalls` works).
+    pub fn set_override(&mut self, assignment: &str) -> Result<(), ConfigError> {
+        let mut parts = assignment.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let raw = match parts.next() {
+            Some(raw) => raw.trim(),
+            None => return Err(ConfigError::invalid(key.to_string(), "expected `key=value`.".to_string()))
+        };
+        if key.is_empty() || key.split('.').any(|part| part.is_empty()) {
+            return Err(ConfigError::invalid(key.to_string(), "is not a valid key.".to_string()));
+        }
+        self.set(key, parse_value(raw))
+    }
+
+    /// the top level of the configuration.
+    pub fn root(&self) -> Section {
+        Section {
+            path: String::new(),
+            table: Some(&self.content)
+        }
+    }
+
+    pub fn get_table<'a>(&'a self, table: Option<&Section<'a>>, name: &str) -> Result<Section<'a>, ConfigError> {
+        let root = self.root();
+        let lookup = table.unwrap_or(&root);
+        let value = try!(lookup.lookup(name));
+        match value.as_table() {
+            Some(t) => Ok(Section { path: lookup.key(name), table: Some(t) }),
+            None => Err(lookup.wrong_type(name, "a table"))
         }
     }
 
-    pub fn get_table<'a>(&'a self, table: Option<&'a Table>, name: &str) -> &Table {
-        let lookup = table.unwrap_or(&self.content);
-        let value = lookup.get(name).expect(&format!("`{}` was not found.", name)[..]);
-        value.as_table().expect(&format!("`{}` is not a TOML.", name)[..])
+    /// like `get_table`, but a missing table reads as an empty one: its
+    /// entries fall back to their defaults, or are reported missing
+    /// under their full key.
+    pub fn get_optional_table<'a>(&'a self, table: Option<&Section<'a>>, name: &str) -> Result<Section<'a>, ConfigError> {
+        let root = self.root();
+        let lookup = table.unwrap_or(&root);
+        if lookup.contains(name) {
+            self.get_table(Some(lookup), name)
+        } else {
+            Ok(Section { path: lookup.key(name), table: None })
+        }
     }
 
-    pub fn get_listing(&self, table: &Table, excluded: Vec<&str>) -> Vec<String> {
+    pub fn get_listing(&self, table: &Section, excluded: Vec<&str>) -> Vec<String> {
         let invalid: HashSet<&str> = excluded.into_iter().collect();
-        table.keys().cloned().filter(|k| !invalid.contains(&k[..])).collect()
+        let keys: Vec<String> = table.table.map(|t| t.keys().cloned().collect()).unwrap_or(vec![]);
+        keys.into_iter().filter(|k| !invalid.contains(&k[..])).collect()
     }
 
-    pub fn get_float<'a>(&'a self, table: &'a Table, name: &str) -> f64 {
-        let value = table.get(name).expect(&format!("`{}` was not found.", name)[..]);
-        value.as_float().expect(&format!("`{}` is not a float.", name)[..])
+    pub fn get_float(&self, table: &Section, name: &str) -> Result<f64, ConfigError> {
+        let value = try!(table.lookup(name));
+        value.as_float().ok_or(table.wrong_type(name, "a float"))
     }
 
-    pub fn get_integer<'a>(&'a self, table: &'a Table, name: &str) -> i64 {
-        let value = table.get(name).expect(&format!("`{}` was not found.", name)[..]);
-        value.as_integer().expect(&format!("`{}` is not an integer.", name)[..])
+    pub fn get_integer(&self, table: &Section, name: &str) -> Result<i64, ConfigError> {
+        let value = try!(table.lookup(name));
+        value.as_integer().ok_or(table.wrong_type(name, "an integer"))
     }
 
-    pub fn get_char<'a>(&'a self, table: &'a Table, name: &str) -> char {
-        let value = table.get(name).expect(&format!("`{}` was not found.", name)[..]);
-        let contents = value.as_str().expect(&format!("`{}` is not a char.", name)[..]);
-        if contents.len() != 1 {
-            panic!("{} is a string, but expected a character.", name);
+    pub fn get_char(&self, table: &Section, name: &str) -> Result<char, ConfigError> {
+        let value = try!(table.lookup(name));
+        let contents = try!(value.as_str().ok_or(table.wrong_type(name, "a character")));
+        if contents.chars().count() != 1 {
+            return Err(table.wrong_type(name, "a single character"));
         }
-        contents.chars().next().unwrap()
+        Ok(contents.chars().next().unwrap())
     }
 
-    pub fn get_string<'a>(&'a self, table: &'a Table, name: &str) -> &str {
-        let value = table.get(name).expect(&format!("`{}` was not found.", name)[..]);
-        value.as_str().expect(&format!("`{}` is not a string.", name)[..])
+    pub fn get_string<'a>(&self, table: &Section<'a>, name: &str) -> Result<&'a str, ConfigError> {
+        let value = try!(table.lookup(name));
+        value.as_str().ok_or(table.wrong_type(name, "a string"))
     }
 
-    pub fn get_array<T: Decodable>(&self, table: &Table, name: &str) -> Vec<T> {
-        let value = table.get(name).expect(&format!("`{}` was not found.", name)[..]);
-        let arr = value.as_slice().expect(&format!("`{}` is not an array.", name)[..]);
-        arr.iter().map(|v| decode(v.clone()).unwrap()).collect()
+    pub fn get_array<T: Decodable>(&self, table: &Section, name: &str) -> Result<Vec<T>, ConfigError> {
+        let value = try!(table.lookup(name));
+        let arr = try!(value.as_slice().ok_or(table.wrong_type(name, "an array")));
+        arr.iter().enumerate().map(|(i, v)| {
+            decode(v.clone()).ok_or(ConfigError::new(format!("{}[{}]", table.key(name), i),
+                                                     ConfigErrorKind::WrongType("of the expected type")))
+        }).collect()
     }
 
-    pub fn get_default<T: Decodable>(&self, table: &Table, name: &str, val: T) -> T {
+    /// the value if present, otherwise the default. a value of the
+    /// wrong type is an error rather than silently ignored.
+    pub fn get_default<T: Decodable>(&self, table: &Section, name: &str, val: T) -> Result<T, ConfigError> {
         match table.get(name) {
-            Some(value) => decode(value.clone()).unwrap_or(val),
-            None => val
+            Some(value) => decode(value.clone()).ok_or(table.wrong_type(name, "of the expected type")),
+            None => Ok(val)
         }
     }
 }
 
+/// read a single TOML value, falling back to a string.
+pub fn parse_value(raw: &str) -> Value {
+    let document = format!("value = {}", raw);
+    match Parser::new(&document[..]).parse() {
+        Some(mut table) => table.remove("value").unwrap_or(Value::String(raw.to_string())),
+        None => Value::String(raw.to_string())
+    }
+}
+
 const DEFAULT_TILE_SIZE: i64 = 16;
 
 pub struct SpriteConfig;
@@ -121,9 +397,8 @@ impl SpriteConfig {
         coords
     }
 
-    /// given a TOML configuration file, extract the relevant
-    /// spritesheet information. returns a hashmap of `Sprite`s.
-    pub fn process_spritesheet(toml_path: &PathBuf) -> HashMap<String, Vec<SpriteRect>> {
+    // read the `sprites` table of a spritesheet configuration file.
+    fn read_sprites(toml_path: &PathBuf) -> Table {
         let mut toml_file = File::open(toml_path).unwrap();
         let mut contents = String::new();
         match toml_file.read_to_string(&mut contents) {
@@ -132,7 +407,21 @@ impl SpriteConfig {
         };
         let value = Parser::new(&contents[..]).parse().expect("Configuration file is not valid TOML.");
         let sprites = value.get("sprites").expect("Configuration file does not have `sprites` entry.");
-        let sprites_table = sprites.as_table().expect("`sprites` entry is not a TOML table.");
+        sprites.as_table().expect("`sprites` entry is not a TOML table.").clone()
+    }
+
+    /// the spritesheet-wide tile size (sprite tables may override it).
+    pub fn tile_size(toml_path: &PathBuf) -> (i32, i32) {
+        let sprites_table = SpriteConfig::read_sprites(toml_path);
+        let tile_width = SpriteConfig::defaults(&sprites_table, "tile_width", DEFAULT_TILE_SIZE);
+        let tile_height = SpriteConfig::defaults(&sprites_table, "tile_height", DEFAULT_TILE_SIZE);
+        (tile_width as i32, tile_height as i32)
+    }
+
+    /// given a TOML configuration file, extract the relevant
+    /// spritesheet information. returns a hashmap of `Sprite`s.
+    pub fn process_spritesheet(toml_path: &PathBuf) -> HashMap<String, Vec<SpriteRect>> {
+        let sprites_table = &SpriteConfig::read_sprites(toml_path);
         let tile_width = SpriteConfig::defaults(sprites_table, "tile_width", DEFAULT_TILE_SIZE);
         let tile_height = SpriteConfig::defaults(sprites_table, "tile_height", DEFAULT_TILE_SIZE);
         let mut sprites = HashMap::new();
@@ -166,3 +455,61 @@ impl SpriteConfig {
         sprites
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path};
+    use toml::{Value};
+    use super::{Config, ConfigErrorKind};
+
+    fn config(contents: &str) -> Config {
+        Config::parse("test.toml", contents).unwrap()
+    }
+
+    #[test]
+    fn syntax_errors_have_positions() {
+        let errors = Config::parse("test.toml", "[main]\nthreads = 4\nfps = = 10\n").unwrap_err();
+        assert!(!errors.is_empty());
+        assert!(errors.errors.iter().all(|error| error.key.starts_with("test.toml:3:")));
+    }
+
+    #[test]
+    fn missing_files_are_errors() {
+        let errors = Config::new(Path::new("no/such/config.toml")).unwrap_err();
+        assert_eq!(errors.errors.len(), 1);
+        assert_eq!(errors.errors[0].key, Path::new("no/such/config.toml").display().to_string());
+    }
+
+    #[test]
+    fn set_creates_tables() {
+        let mut config = config("[mu-lambda]\nmu = 10\n");
+        config.set("mu-lambda.lambda", Value::Integer(20)).unwrap();
+        config.set("novelty.enabled", Value::Boolean(true)).unwrap();
+        let vars = config.get_table(None, "mu-lambda").unwrap();
+        assert_eq!(config.get_integer(&vars, "lambda"), Ok(20));
+        assert_eq!(config.get_integer(&vars, "mu"), Ok(10));
+        let vars = config.get_table(None, "novelty").unwrap();
+        assert_eq!(config.get_default(&vars, "enabled", false), Ok(true));
+    }
+
+    #[test]
+    fn set_through_a_value_fails() {
+        let mut config = config("[mu-lambda]\nmu = 10\n");
+        let error = config.set("mu-lambda.mu.x", Value::Integer(1)).unwrap_err();
+        assert_eq!(error.key, "mu-lambda.mu");
+        assert_eq!(error.kind, ConfigErrorKind::WrongType("a table"));
+        assert!(config.set_override("mu-lambda.mu.x=1").is_err());
+        assert!(config.set_override("mu-lambda..x=1").is_err());
+        assert!(config.set_override("mu-lambda.mu").is_err());
+    }
+
+    #[test]
+    fn overrides_are_read_as_toml() {
+        let mut config = config("");
+        config.set_override("mu-lambda.mu=50").unwrap();
+        config.set_override("mu-lambda.strategy=ListOfWalls").unwrap();
+        let vars = config.get_table(None, "mu-lambda").unwrap();
+        assert_eq!(config.get_integer(&vars, "mu"), Ok(50));
+        assert_eq!(config.get_string(&vars, "strategy"), Ok("ListOfWalls"));
+    }
+}
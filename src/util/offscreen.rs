@@ -0,0 +1,117 @@
+use std::path::{Path};
+use std::fs::{File};
+use std::collections::{HashMap};
+use image::{self, GenericImage, DynamicImage, ImageBuffer, ImageFormat, Rgba};
+
+use util::config::{SpriteConfig};
+use util::sprite::{SpriteRect};
+use util::spritesheet::{SpriteSheet};
+
+pub type Canvas = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// a spritesheet kept in main memory, for drawing without OpenGL.
+/// uses the same TOML description as `SpriteSheet`.
+pub struct OffscreenSpriteSheet {
+    sheet: DynamicImage,
+    sprites: HashMap<String, Vec<SpriteRect>>,
+}
+
+impl OffscreenSpriteSheet {
+
+    pub fn new(filepath: &Path) -> OffscreenSpriteSheet {
+        let sheet = match image::open(filepath) {
+            Ok(i) => i,
+            Err(why) => panic!("Could not load spritesheet {}: {:?}", filepath.display(), why)
+        };
+        let toml_path = SpriteSheet::location(filepath).expect("No spritesheet configuration file.");
+        OffscreenSpriteSheet {
+            sheet: sheet,
+            sprites: SpriteConfig::process_spritesheet(&toml_path),
+        }
+    }
+
+    /// composite a sprite onto the canvas. returns false if there is
+    /// no sprite by that name.
+    pub fn draw(&self, canvas: &mut Canvas, name: &str, x: u32, y: u32, index: usize) -> bool {
+        let rects = match self.sprites.get(name) {
+            Some(rects) => rects,
+            None => return false
+        };
+        let (sx, sy, w, h) = rects[index % rects.len()].source();
+        for j in 0..h {
+            for i in 0..w {
+                let (cx, cy) = (x + i, y + j);
+                if cx >= canvas.width() || cy >= canvas.height() {
+                    continue;
+                }
+                let source = self.sheet.get_pixel(sx + i, sy + j);
+                let target = *canvas.get_pixel(cx, cy);
+                canvas.put_pixel(cx, cy, blend(source, target));
+            }
+        }
+        true
+    }
+
+    /// draw a red 'X'.
+    pub fn missing(canvas: &mut Canvas, x: u32, y: u32, w: u32, h: u32) {
+        let red = Rgba { data: [255, 0, 0, 255] };
+        for i in 0..w {
+            let j = i * h / w;
+            for &(cx, cy) in [(x + i, y + j), (x + w - 1 - i, y + j)].iter() {
+                if cx < canvas.width() && cy < canvas.height() {
+                    canvas.put_pixel(cx, cy, red);
+                }
+            }
+        }
+    }
+}
+
+// source-over alpha compositing.
+fn blend(source: Rgba<u8>, target: Rgba<u8>) -> Rgba<u8> {
+    let alpha = source.data[3] as u32;
+    match alpha {
+        0 => target,
+        255 => source,
+        _ => {
+            let mut data = [0; 4];
+            for c in 0..3 {
+                data[c] = ((source.data[c] as u32 * alpha + target.data[c] as u32 * (255 - alpha)) / 255) as u8;
+            }
+            data[3] = (alpha + target.data[3] as u32 * (255 - alpha) / 255) as u8;
+            Rgba { data: data }
+        }
+    }
+}
+
+/// which image format to use for this file. only formats that image
+/// 0.5 can encode are offered (it reads more than it writes), and only
+/// when their `image_*` cargo feature is enabled.
+fn output_format(path: &Path) -> Result<ImageFormat, String> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let (format, feature, enabled) = match &extension[..] {
+        "png" => (ImageFormat::PNG, "image_png", cfg!(feature = "image_png")),
+        "jpg" | "jpeg" => (ImageFormat::JPEG, "image_jpeg", cfg!(feature = "image_jpeg")),
+        "ppm" => (ImageFormat::PPM, "image_ppm", cfg!(feature = "image_ppm")),
+        _ => return Err(format!("cannot write `{}` images (expected png, jpeg or ppm)", extension))
+    };
+    if enabled {
+        Ok(format)
+    } else {
+        Err(format!("`{}` support is not enabled; rebuild with the `{}` feature", extension, feature))
+    }
+}
+
+pub fn save_canvas(canvas: &Canvas, path: &Path) {
+    let format = match output_format(path) {
+        Ok(f) => f,
+        Err(why) => panic!("Could not save {}: {}", path.display(), why)
+    };
+    let mut file = match File::create(path) {
+        Ok(f) => f,
+        Err(why) => panic!("Could not create {}: {}", path.display(), why)
+    };
+    match DynamicImage::ImageRgba8(canvas.clone()).save(&mut file, format) {
+        Err(why) => panic!("Could not write {}: {:?}", path.display(), why),
+        _ => ()
+    }
+}
@@ -30,6 +30,29 @@ impl Sprite {
         });
     }
 
+    /// draw at `scale` times the sprite's size (e.g. for thumbnails).
+    pub fn draw_scaled(&self, gl: &mut GlGraphics, viewport: Viewport, x: f64, y: f64, scale: f64, index: usize) {
+        let idx = index % self.images.len();
+        let image = self.images[idx];
+        gl.draw(viewport, |c, gl| {
+            let transform = c.transform.trans(x, y).scale(scale, scale);
+            image.draw(&*self.texture, default_draw_state(), transform, gl);
+        });
+    }
+
+    /// draw the outline of a rectangle.
+    pub fn outline(gl: &mut GlGraphics, viewport: Viewport, x: i32, y: i32, w: i32, h: i32, color: [f32; 4]) {
+        let line = Line::new(color, 1.0);
+        gl.draw(viewport, |c, gl| {
+            let transform = c.transform.trans(x as f64, y as f64);
+            let (w, h) = (w as f64, h as f64);
+            line.draw([0.0, 0.0, w, 0.0], default_draw_state(), transform, gl);
+            line.draw([w, 0.0, w, h], default_draw_state(), transform, gl);
+            line.draw([w, h, 0.0, h], default_draw_state(), transform, gl);
+            line.draw([0.0, h, 0.0, 0.0], default_draw_state(), transform, gl);
+        });
+    }
+
     /// draw a red 'X'.
     pub fn missing(gl: &mut GlGraphics, viewport: Viewport, x: i32, y: i32, w: i32, h: i32) {
         let line = Line::new([1.0, 0.0, 0.0, 1.0], 1.0);
@@ -60,6 +83,11 @@ impl SpriteRect {
 
     pub fn get_height(&self) -> i32 { self.h }
 
+    /// the area on the texture, in pixels: (x, y, width, height).
+    pub fn source(&self) -> (u32, u32, u32, u32) {
+        ((self.x * self.w) as u32, (self.y * self.h) as u32, self.w as u32, self.h as u32)
+    }
+
     pub fn to_image(&self) -> Image {
         Image {
             color: None,
@@ -0,0 +1,178 @@
+use std::path::{Path};
+use std::io::{Write};
+use std::fs::{File};
+use toml::{Value};
+use time::{precise_time_s};
+use rand::{Rng, thread_rng};
+
+use util::chapter::{Chapter};
+use util::config::{Config, parse_value};
+
+/// one swept key and the values it takes.
+pub struct Axis {
+    key: String,
+    values: Vec<Value>,
+}
+
+impl Axis {
+    /// parse `key=a,b,c` (a list) or `key=start..end:step` (an
+    /// inclusive range; integer if all three parts are integers).
+    pub fn parse(spec: &str) -> Result<Axis, String> {
+        let mut parts = spec.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim().to_string();
+        let raw = match parts.next() {
+            Some(raw) => raw.trim(),
+            None => return Err(format!("`{}`: expected `key=values`.", spec))
+        };
+        let values = if raw.contains("..") {
+            try!(Axis::range(raw).ok_or(format!("`{}`: expected `start..end:step`.", spec)))
+        } else {
+            raw.split(',').map(|v| parse_value(v.trim())).collect()
+        };
+        if key.is_empty() || values.is_empty() {
+            return Err(format!("`{}` does not sweep anything.", spec));
+        }
+        Ok(Axis {
+            key: key,
+            values: values
+        })
+    }
+
+    fn range(raw: &str) -> Option<Vec<Value>> {
+        let mut bounds = raw.splitn(2, "..");
+        let start = bounds.next().unwrap_or("").trim();
+        let mut rest = bounds.next().unwrap_or("").splitn(2, ':');
+        let end = rest.next().unwrap_or("").trim();
+        let step = rest.next().unwrap_or("1").trim();
+        if let (Ok(start), Ok(end), Ok(step)) = (start.parse::<i64>(), end.parse::<i64>(), step.parse::<i64>()) {
+            if step <= 0 {
+                return None;
+            }
+            return Some((start..end + 1).step_by(step).map(Value::Integer).collect());
+        }
+        match (start.parse::<f64>(), end.parse::<f64>(), step.parse::<f64>()) {
+            (Ok(start), Ok(end), Ok(step)) if step > 0.0 => {
+                // multiply rather than accumulate, to avoid drift.
+                let n = ((end - start) / step + 1e-9).floor() as i64;
+                Some((0..n + 1).map(|i| Value::Float(start + i as f64 * step)).collect())
+            }
+            _ => None
+        }
+    }
+}
+
+/// the outcome of one headless run, as reported by a chapter.
+pub struct RunSummary {
+    pub best: f64,
+    pub mean: f64,
+}
+
+// every combination of the axes' values.
+fn combinations(axes: &[Axis]) -> Vec<Vec<Value>> {
+    axes.iter().fold(vec![vec![]], |accum, axis| {
+        let mut next = vec![];
+        for combination in accum.iter() {
+            for value in axis.values.iter() {
+                let mut extended = combination.clone();
+                extended.push(value.clone());
+                next.push(extended);
+            }
+        }
+        next
+    })
+}
+
+fn csv_field(field: String) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace("\"", "\"\""))
+    } else {
+        field
+    }
+}
+
+fn value_field(value: &Value) -> String {
+    match *value {
+        Value::String(ref s) => csv_field(s.clone()),
+        ref other => csv_field(other.to_string())
+    }
+}
+
+/// run the chapter once for every combination of the swept values
+/// (layered on top of `config`), and write one CSV row per run. every
+/// run uses the same seed (`[mu-lambda] seed`, or one drawn for the
+/// whole sweep), so that rows differ only in the swept values.
+pub fn sweep(chapter: &Chapter, config: &Config, axes: &[Axis], out: &Path) -> Result<(), String> {
+    let mut config = config.clone();
+    let seeded = config.get_optional_table(None, "mu-lambda").map(|vars| vars.contains("seed")).unwrap_or(false);
+    if !seeded {
+        let seed: u64 = thread_rng().gen();
+        println!("seed: {}", seed);
+        try!(config.set("mu-lambda.seed", Value::Integer(seed as i64)).map_err(|why| why.to_string()));
+    }
+    let mut file = try!(File::create(out).map_err(|why| format!("Could not create {}: {}", out.display(), why)));
+    let mut header: Vec<String> = axes.iter().map(|axis| csv_field(axis.key.clone())).collect();
+    header.push_all(&["best".to_string(), "mean".to_string(), "runtime".to_string()]);
+    try!(writeln!(file, "{}", header.join(",")).map_err(|why| why.to_string()));
+    let runs = combinations(axes);
+    for (index, values) in runs.iter().enumerate() {
+        let mut current = config.clone();
+        for (axis, value) in axes.iter().zip(values.iter()) {
+            try!(current.set(&axis.key[..], value.clone()).map_err(|why| why.to_string()));
+        }
+        let start = precise_time_s();
+        let summary = try!(chapter.summarize(&current));
+        let runtime = precise_time_s() - start;
+        let mut row: Vec<String> = values.iter().map(value_field).collect();
+        row.push_all(&[summary.best.to_string(), summary.mean.to_string(), format!("{:.3}", runtime)]);
+        try!(writeln!(file, "{}", row.join(",")).map_err(|why| why.to_string()));
+        println!("sweep {}/{}: best {}, mean {}, {:.1}s", index + 1, runs.len(), summary.best, summary.mean, runtime);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use toml::{Value};
+    use super::{Axis, combinations};
+
+    #[test]
+    fn lists() {
+        let axis = Axis::parse("mu-lambda.strategy=RandomSeed, ListOfWalls").unwrap();
+        assert_eq!(axis.key, "mu-lambda.strategy");
+        assert_eq!(axis.values, vec![Value::String("RandomSeed".to_string()), Value::String("ListOfWalls".to_string())]);
+        let axis = Axis::parse("mu-lambda.mu=10,20").unwrap();
+        assert_eq!(axis.values, vec![Value::Integer(10), Value::Integer(20)]);
+    }
+
+    #[test]
+    fn integer_ranges_are_inclusive() {
+        let axis = Axis::parse("mu-lambda.mu=10..50:20").unwrap();
+        assert_eq!(axis.values, vec![Value::Integer(10), Value::Integer(30), Value::Integer(50)]);
+        let axis = Axis::parse("mu-lambda.mu=1..3").unwrap();
+        assert_eq!(axis.values, vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)]);
+    }
+
+    #[test]
+    fn float_ranges_do_not_drift() {
+        let axis = Axis::parse("mu-lambda.mutation=0.1..0.5:0.1").unwrap();
+        assert_eq!(axis.values.len(), 5);
+        match axis.values[4] {
+            Value::Float(f) => assert!((f - 0.5).abs() < 1e-9),
+            ref other => panic!("{:?} is not a float", other)
+        }
+    }
+
+    #[test]
+    fn bad_specs_are_rejected() {
+        assert!(Axis::parse("mu-lambda.mu").is_err());
+        assert!(Axis::parse("=1,2").is_err());
+        assert!(Axis::parse("mu-lambda.mu=1..5:0").is_err());
+        assert!(Axis::parse("mu-lambda.mu=1..x").is_err());
+    }
+
+    #[test]
+    fn every_combination() {
+        let axes = vec![Axis::parse("a=1,2").unwrap(), Axis::parse("b=x,y,z").unwrap()];
+        assert_eq!(combinations(&axes[..]).len(), 6);
+    }
+}
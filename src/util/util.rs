@@ -1,4 +1,4 @@
-use rand::{Rng};
+use rand::{Rng, SeedableRng, XorShiftRng};
 
 // Fisher-Yates algorithm
 pub fn shuffle<'a, R: Rng, T: Clone>(rng: &mut R, array: &'a mut [T]) {
@@ -13,3 +13,47 @@ pub fn shuffle<'a, R: Rng, T: Clone>(rng: &mut R, array: &'a mut [T]) {
 pub fn odds<R: Rng>(rng: &mut R, num: u64, den: u64) -> bool {
     num > rng.gen_range(0, den)
 }
+
+// splitmix64 finaliser; scrambles the bits of a 64-bit value.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// derive an independent random stream from a seed and a path of
+/// indices (e.g. generation and individual). the stream does not
+/// depend on which thread ends up doing the work.
+pub fn derive_rng(seed: u64, stream: &[u64]) -> XorShiftRng {
+    let a = stream.iter().fold(mix(seed), |accum, &s| mix(accum ^ s));
+    let b = mix(a);
+    // xorshift must not be seeded with all zeroes.
+    let words = [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32 | 1];
+    XorShiftRng::from_seed(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng};
+    use super::{derive_rng};
+
+    fn draws(seed: u64, stream: &[u64]) -> Vec<u64> {
+        let mut rng = derive_rng(seed, stream);
+        (0..8).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn same_seed_and_stream_draw_the_same() {
+        assert_eq!(draws(42, &[1, 2, 3]), draws(42, &[1, 2, 3]));
+        assert_eq!(draws(0, &[]), draws(0, &[]));
+    }
+
+    #[test]
+    fn streams_differ() {
+        assert!(draws(42, &[1, 2, 3]) != draws(43, &[1, 2, 3]));
+        assert!(draws(42, &[1, 2, 3]) != draws(42, &[1, 2, 4]));
+        assert!(draws(42, &[1, 2]) != draws(42, &[2, 1]));
+        assert!(draws(42, &[]) != draws(42, &[0]));
+    }
+}